@@ -34,9 +34,11 @@ use super::bucket::{
     DateHistogramAggregationReq, HistogramAggregation, RangeAggregation, TermsAggregation,
 };
 use super::metric::{
-    AverageAggregation, CountAggregation, MaxAggregation, MinAggregation,
-    PercentilesAggregationReq, StatsAggregation, SumAggregation,
+    AverageAggregation, CountAggregation, ExtendedStatsAggregation, MaxAggregation, MinAggregation,
+    PercentilesAggregationReq, StatsAggregation, SumAggregation, TopMetricsAggregation,
+    WeightedAvgAggregation,
 };
+use super::pipeline::DerivativeAggregation;
 
 /// The top-level aggregation request structure, which contains [`Aggregation`] and their user
 /// defined names. It is also used in buckets aggregations to define sub-aggregations.
@@ -93,7 +95,15 @@ impl Aggregation {
     }
 
     fn get_fast_field_names(&self, fast_field_names: &mut HashSet<String>) {
-        fast_field_names.insert(self.agg.get_fast_field_name().to_string());
+        if self.agg.as_derivative().is_none() {
+            fast_field_names.insert(self.agg.get_fast_field_name().to_string());
+        }
+        if let AggregationVariants::WeightedAvg(weighted_avg) = &self.agg {
+            fast_field_names.insert(weighted_avg.weight_field_name().to_string());
+        }
+        if let AggregationVariants::TopMetrics(top_metrics) = &self.agg {
+            fast_field_names.extend(top_metrics.metric_field_names().map(ToString::to_string));
+        }
         fast_field_names.extend(get_fast_field_names(&self.sub_aggregation));
     }
 }
@@ -141,16 +151,33 @@ pub enum AggregationVariants {
     /// extracted values.
     #[serde(rename = "stats")]
     Stats(StatsAggregation),
+    /// Computes the statistics of `stats`, plus the sum of squares, the variance and the
+    /// standard deviation of the extracted values.
+    #[serde(rename = "extended_stats")]
+    ExtendedStats(ExtendedStatsAggregation),
     /// Computes the sum of the extracted values.
     #[serde(rename = "sum")]
     Sum(SumAggregation),
     /// Computes the sum of the extracted values.
     #[serde(rename = "percentiles")]
     Percentiles(PercentilesAggregationReq),
+    /// Computes the weighted average of the extracted values.
+    #[serde(rename = "weighted_avg")]
+    WeightedAvg(WeightedAvgAggregation),
+    /// Returns the metric field values of the top documents by a sort field.
+    #[serde(rename = "top_metrics")]
+    TopMetrics(TopMetricsAggregation),
+
+    // Pipeline aggregation types
+    /// Computes the derivative of a sibling metric over the buckets of the parent histogram.
+    #[serde(rename = "derivative")]
+    Derivative(DerivativeAggregation),
 }
 
 impl AggregationVariants {
     /// Returns the name of the field used by the aggregation.
+    ///
+    /// Pipeline aggregations don't read a field and return an empty string.
     pub fn get_fast_field_name(&self) -> &str {
         match self {
             AggregationVariants::Terms(terms) => terms.field.as_str(),
@@ -162,8 +189,12 @@ impl AggregationVariants {
             AggregationVariants::Max(max) => max.field_name(),
             AggregationVariants::Min(min) => min.field_name(),
             AggregationVariants::Stats(stats) => stats.field_name(),
+            AggregationVariants::ExtendedStats(extended_stats) => extended_stats.field_name(),
             AggregationVariants::Sum(sum) => sum.field_name(),
             AggregationVariants::Percentiles(per) => per.field_name(),
+            AggregationVariants::WeightedAvg(weighted_avg) => weighted_avg.field_name(),
+            AggregationVariants::TopMetrics(top_metrics) => top_metrics.field_name(),
+            AggregationVariants::Derivative(_) => "",
         }
     }
 
@@ -189,12 +220,47 @@ impl AggregationVariants {
         }
     }
 
+    pub(crate) fn as_average(&self) -> Option<&AverageAggregation> {
+        match &self {
+            AggregationVariants::Average(avg) => Some(avg),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_sum(&self) -> Option<&SumAggregation> {
+        match &self {
+            AggregationVariants::Sum(sum) => Some(sum),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_extended_stats(&self) -> Option<&ExtendedStatsAggregation> {
+        match &self {
+            AggregationVariants::ExtendedStats(extended_stats) => Some(extended_stats),
+            _ => None,
+        }
+    }
+
     pub(crate) fn as_percentile(&self) -> Option<&PercentilesAggregationReq> {
         match &self {
             AggregationVariants::Percentiles(percentile_req) => Some(percentile_req),
             _ => None,
         }
     }
+
+    pub(crate) fn as_top_metrics(&self) -> Option<&TopMetricsAggregation> {
+        match &self {
+            AggregationVariants::TopMetrics(top_metrics) => Some(top_metrics),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_derivative(&self) -> Option<&DerivativeAggregation> {
+        match &self {
+            AggregationVariants::Derivative(derivative) => Some(derivative),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -210,7 +276,11 @@ mod tests {
             "price_max": { "max": { "field": "price" } },
             "price_min": { "min": { "field": "price" } },
             "price_stats": { "stats": { "field": "price" } },
-            "price_sum": { "sum": { "field": "price" } }
+            "price_extended_stats": { "extended_stats": { "field": "price" } },
+            "price_sum": { "sum": { "field": "price" } },
+            "price_weighted_avg": {
+                "weighted_avg": { "value": { "field": "price" }, "weight": { "field": "qty" } }
+            }
         }"#;
         let _agg_req: Aggregations = serde_json::from_str(agg_req_json).unwrap();
     }
@@ -254,7 +324,10 @@ mod tests {
             "price_max": { "max": { "field": "price" } },
             "price_min": { "min": { "field": "price" } },
             "price_stats": { "stats": { "field": "price" } },
-            "price_sum": { "sum": { "field": "price" } }
+            "price_sum": { "sum": { "field": "price" } },
+            "price_weighted_avg": {
+                "weighted_avg": { "value": { "field": "price" }, "weight": { "field": "qty" } }
+            }
         }"#;
         let agg_req: Aggregations = serde_json::from_str(agg_req_json).unwrap();
 
@@ -276,6 +349,16 @@ mod tests {
         assert!(
             matches!(&agg_req.get("price_sum").unwrap().agg, AggregationVariants::Sum(sum) if sum.field == "price")
         );
+        assert!(
+            matches!(&agg_req.get("price_weighted_avg").unwrap().agg, AggregationVariants::WeightedAvg(weighted_avg) if weighted_avg.value.field == "price" && weighted_avg.weight.field == "qty")
+        );
+        assert_eq!(
+            get_fast_field_names(&agg_req),
+            ["price", "qty"]
+                .into_iter()
+                .map(ToString::to_string)
+                .collect::<HashSet<_>>()
+        );
     }
 
     #[test]
@@ -8,13 +8,13 @@ use super::bucket::{
     DateHistogramAggregationReq, HistogramAggregation, RangeAggregation, TermsAggregation,
 };
 use super::metric::{
-    AverageAggregation, CountAggregation, MaxAggregation, MinAggregation, StatsAggregation,
-    SumAggregation,
+    AverageAggregation, CountAggregation, ExtendedStatsAggregation, MaxAggregation, MinAggregation,
+    StatsAggregation, SumAggregation,
 };
 use super::segment_agg_result::AggregationLimits;
 use super::VecWithNames;
 use crate::aggregation::{f64_to_fastfield_u64, Key};
-use crate::SegmentReader;
+use crate::{SegmentOrdinal, SegmentReader};
 
 #[derive(Default)]
 pub(crate) struct AggregationsWithAccessor {
@@ -48,6 +48,12 @@ pub struct AggregationWithAccessor {
     /// (instead bein set in `agg`).
     /// If this needs to used by other aggregations, we need to refactor this.
     pub(crate) accessors: Vec<Column<u64>>,
+    /// The weight column of the weighted average aggregation.
+    pub(crate) weight_accessor: Option<(Column<u64>, ColumnType)>,
+    /// The metric columns of the top metrics aggregation, in request order.
+    pub(crate) metric_accessors: Vec<(Column<u64>, ColumnType)>,
+    /// The ordinal of the segment in the searcher.
+    pub(crate) segment_ordinal: SegmentOrdinal,
     pub(crate) agg: Aggregation,
 }
 
@@ -57,6 +63,7 @@ impl AggregationWithAccessor {
         agg: &Aggregation,
         sub_aggregation: &Aggregations,
         reader: &SegmentReader,
+        segment_ordinal: SegmentOrdinal,
         limits: AggregationLimits,
     ) -> crate::Result<Vec<AggregationWithAccessor>> {
         let add_agg_with_accessor = |accessor: Column<u64>,
@@ -66,10 +73,14 @@ impl AggregationWithAccessor {
             let res = AggregationWithAccessor {
                 accessor,
                 accessors: Vec::new(),
+                weight_accessor: None,
+                metric_accessors: Vec::new(),
+                segment_ordinal,
                 field_type: column_type,
                 sub_aggregation: get_aggs_with_segment_accessor_and_validate(
                     sub_aggregation,
                     reader,
+                    segment_ordinal,
                     &limits,
                 )?,
                 agg: agg.clone(),
@@ -167,10 +178,14 @@ impl AggregationWithAccessor {
                         missing_value_for_accessor: None,
                         accessor: accessors[0].clone(),
                         accessors,
+                        weight_accessor: None,
+                        metric_accessors: Vec::new(),
+                        segment_ordinal,
                         field_type: ColumnType::U64,
                         sub_aggregation: get_aggs_with_segment_accessor_and_validate(
                             sub_aggregation,
                             reader,
+                            segment_ordinal,
                             &limits,
                         )?,
                         agg: agg.clone(),
@@ -199,10 +214,14 @@ impl AggregationWithAccessor {
                         missing_value_for_accessor,
                         accessor,
                         accessors: Vec::new(),
+                        weight_accessor: None,
+                        metric_accessors: Vec::new(),
+                        segment_ordinal,
                         field_type: column_type,
                         sub_aggregation: get_aggs_with_segment_accessor_and_validate(
                             sub_aggregation,
                             reader,
+                            segment_ordinal,
                             &limits,
                         )?,
                         agg: agg.clone(),
@@ -228,6 +247,9 @@ impl AggregationWithAccessor {
             | Stats(StatsAggregation {
                 field: field_name, ..
             })
+            | ExtendedStats(ExtendedStatsAggregation {
+                field: field_name, ..
+            })
             | Sum(SumAggregation {
                 field: field_name, ..
             }) => {
@@ -243,6 +265,37 @@ impl AggregationWithAccessor {
                 )?;
                 add_agg_with_accessor(accessor, column_type, &mut res)?;
             }
+            WeightedAvg(weighted_avg) => {
+                let (accessor, column_type) = get_ff_reader(
+                    reader,
+                    weighted_avg.field_name(),
+                    Some(get_numeric_or_date_column_types()),
+                )?;
+                let weight_accessor = get_ff_reader(
+                    reader,
+                    weighted_avg.weight_field_name(),
+                    Some(get_numeric_or_date_column_types()),
+                )?;
+                add_agg_with_accessor(accessor, column_type, &mut res)?;
+                res[0].weight_accessor = Some(weight_accessor);
+            }
+            TopMetrics(top_metrics) => {
+                let (accessor, column_type) = get_ff_reader(
+                    reader,
+                    top_metrics.field_name(),
+                    Some(get_numeric_or_date_column_types()),
+                )?;
+                let metric_accessors = top_metrics
+                    .metric_field_names()
+                    .map(|field_name| {
+                        get_ff_reader(reader, field_name, Some(get_numeric_or_date_column_types()))
+                    })
+                    .collect::<crate::Result<Vec<_>>>()?;
+                add_agg_with_accessor(accessor, column_type, &mut res)?;
+                res[0].metric_accessors = metric_accessors;
+            }
+            // Pipeline aggregations are computed on the final result, and are not collected.
+            Derivative(_) => {}
         };
 
         Ok(res)
@@ -283,6 +336,7 @@ fn get_numeric_or_date_column_types() -> &'static [ColumnType] {
 pub(crate) fn get_aggs_with_segment_accessor_and_validate(
     aggs: &Aggregations,
     reader: &SegmentReader,
+    segment_ordinal: SegmentOrdinal,
     limits: &AggregationLimits,
 ) -> crate::Result<AggregationsWithAccessor> {
     let mut aggss = Vec::new();
@@ -291,6 +345,7 @@ pub(crate) fn get_aggs_with_segment_accessor_and_validate(
             agg,
             agg.sub_aggregation(),
             reader,
+            segment_ordinal,
             limits.clone(),
         )?;
         for agg in aggs {
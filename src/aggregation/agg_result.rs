@@ -8,7 +8,9 @@ use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 
 use super::bucket::GetDocCount;
-use super::metric::{PercentilesMetricResult, SingleMetricResult, Stats};
+use super::metric::{
+    ExtendedStats, PercentilesMetricResult, SingleMetricResult, Stats, TopMetricsMetricResult,
+};
 use super::{AggregationError, Key};
 use crate::TantivyError;
 
@@ -88,10 +90,18 @@ pub enum MetricResult {
     Min(SingleMetricResult),
     /// Stats metric result.
     Stats(Stats),
+    /// Extended stats metric result.
+    ExtendedStats(ExtendedStats),
     /// Sum metric result.
     Sum(SingleMetricResult),
     /// Sum metric result.
     Percentiles(PercentilesMetricResult),
+    /// Weighted average metric result.
+    WeightedAvg(SingleMetricResult),
+    /// Top metrics metric result.
+    TopMetrics(TopMetricsMetricResult),
+    /// Derivative pipeline result.
+    Derivative(SingleMetricResult),
 }
 
 impl MetricResult {
@@ -102,7 +112,11 @@ impl MetricResult {
             MetricResult::Max(max) => Ok(max.value),
             MetricResult::Min(min) => Ok(min.value),
             MetricResult::Stats(stats) => stats.get_value(agg_property),
+            MetricResult::ExtendedStats(extended_stats) => extended_stats.get_value(agg_property),
             MetricResult::Sum(sum) => Ok(sum.value),
+            MetricResult::WeightedAvg(weighted_avg) => Ok(weighted_avg.value),
+            MetricResult::TopMetrics(top_metrics) => top_metrics.get_value(agg_property),
+            MetricResult::Derivative(derivative) => Ok(derivative.value),
             MetricResult::Percentiles(_) => Err(TantivyError::AggregationError(
                 AggregationError::InvalidRequest("percentiles can't be used to order".to_string()),
             )),
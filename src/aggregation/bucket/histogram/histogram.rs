@@ -17,6 +17,7 @@ use crate::aggregation::intermediate_agg_result::{
     IntermediateAggregationResult, IntermediateAggregationResults, IntermediateBucketResult,
     IntermediateHistogramBucketEntry,
 };
+use crate::aggregation::pipeline::compute_pipeline_aggs;
 use crate::aggregation::segment_agg_result::{
     build_segment_agg_collector, AggregationLimits, SegmentAggregationCollector,
 };
@@ -526,6 +527,8 @@ pub(crate) fn intermediate_histogram_buckets_to_final_buckets(
         }
     }
 
+    compute_pipeline_aggs(&mut buckets, sub_aggregation)?;
+
     Ok(buckets)
 }
 
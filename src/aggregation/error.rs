@@ -36,4 +36,12 @@ pub enum AggregationError {
         /// Current num buckets
         current: u32,
     },
+    /// Sum of integer values out of range
+    #[error("The sum on field {field:?} exceeds the range of the field type: {sum}")]
+    SumOverflow {
+        /// Field of the sum aggregation
+        field: String,
+        /// Exact sum of the values
+        sum: i128,
+    },
 }
@@ -18,9 +18,11 @@ use super::bucket::{
     GetDocCount, Order, OrderTarget, RangeAggregation, TermsAggregation,
 };
 use super::metric::{
-    IntermediateAverage, IntermediateCount, IntermediateMax, IntermediateMin, IntermediateStats,
-    IntermediateSum, PercentilesCollector,
+    IntermediateAverage, IntermediateCount, IntermediateExtendedStats, IntermediateMax,
+    IntermediateMin, IntermediateStats, IntermediateSum, IntermediateTopMetrics,
+    IntermediateWeightedAvg, PercentilesCollector,
 };
+use super::pipeline::validate_pipeline_aggs;
 use super::segment_agg_result::AggregationLimits;
 use super::{format_date, AggregationError, Key, SerializedKey};
 use crate::aggregation::agg_result::{AggregationResults, BucketEntries, BucketEntry};
@@ -97,6 +99,7 @@ impl IntermediateAggregationResults {
         req: Aggregations,
         limits: &AggregationLimits,
     ) -> crate::Result<AggregationResults> {
+        validate_pipeline_aggs(&req, None)?;
         let res = self.into_final_result_internal(&req, limits)?;
         let bucket_count = res.get_bucket_count() as u32;
         if bucket_count > limits.get_bucket_limit() {
@@ -131,8 +134,11 @@ impl IntermediateAggregationResults {
         // Handle empty results
         if results.len() != req.len() {
             for (key, req) in req.iter() {
-                if !results.contains_key(key) {
-                    let empty_res = empty_from_req(req);
+                if results.contains_key(key) {
+                    continue;
+                }
+                // Pipeline aggregations are computed by their parent bucket aggregation.
+                if let Some(empty_res) = empty_from_req(req) {
                     results.insert(key.to_string(), empty_res.into_final_result(req, limits)?);
                 }
             }
@@ -144,8 +150,9 @@ impl IntermediateAggregationResults {
     pub(crate) fn empty_from_req(req: &Aggregations) -> Self {
         let mut aggs_res: FxHashMap<String, IntermediateAggregationResult> = FxHashMap::default();
         for (key, req) in req.iter() {
-            let empty_res = empty_from_req(req);
-            aggs_res.insert(key.to_string(), empty_res);
+            if let Some(empty_res) = empty_from_req(req) {
+                aggs_res.insert(key.to_string(), empty_res);
+            }
         }
 
         Self { aggs_res }
@@ -163,9 +170,11 @@ impl IntermediateAggregationResults {
     }
 }
 
-pub(crate) fn empty_from_req(req: &Aggregation) -> IntermediateAggregationResult {
+/// Returns the empty intermediate result of `req`, or `None` for pipeline aggregations, which
+/// have no intermediate result.
+pub(crate) fn empty_from_req(req: &Aggregation) -> Option<IntermediateAggregationResult> {
     use AggregationVariants::*;
-    match req.agg {
+    let empty_res = match &req.agg {
         Terms(_) => IntermediateAggregationResult::Bucket(IntermediateBucketResult::Terms(
             Default::default(),
         )),
@@ -193,13 +202,24 @@ pub(crate) fn empty_from_req(req: &Aggregation) -> IntermediateAggregationResult
         Stats(_) => IntermediateAggregationResult::Metric(IntermediateMetricResult::Stats(
             IntermediateStats::default(),
         )),
+        ExtendedStats(_) => IntermediateAggregationResult::Metric(
+            IntermediateMetricResult::ExtendedStats(IntermediateExtendedStats::default()),
+        ),
         Sum(_) => IntermediateAggregationResult::Metric(IntermediateMetricResult::Sum(
             IntermediateSum::default(),
         )),
         Percentiles(_) => IntermediateAggregationResult::Metric(
             IntermediateMetricResult::Percentiles(PercentilesCollector::default()),
         ),
-    }
+        WeightedAvg(_) => IntermediateAggregationResult::Metric(
+            IntermediateMetricResult::WeightedAvg(IntermediateWeightedAvg::default()),
+        ),
+        TopMetrics(top_metrics) => IntermediateAggregationResult::Metric(
+            IntermediateMetricResult::TopMetrics(IntermediateTopMetrics::from_req(top_metrics)),
+        ),
+        Derivative(_) => return None,
+    };
+    Some(empty_res)
 }
 
 /// An aggregation is either a bucket or a metric.
@@ -222,7 +242,7 @@ impl IntermediateAggregationResult {
                 AggregationResult::BucketResult(bucket.into_final_bucket_result(req, limits)?)
             }
             IntermediateAggregationResult::Metric(metric) => {
-                AggregationResult::MetricResult(metric.into_final_metric_result(req))
+                AggregationResult::MetricResult(metric.into_final_metric_result(req)?)
             }
         };
         Ok(res)
@@ -257,36 +277,61 @@ pub enum IntermediateMetricResult {
     Min(IntermediateMin),
     /// Intermediate stats result.
     Stats(IntermediateStats),
+    /// Intermediate extended stats result.
+    ExtendedStats(IntermediateExtendedStats),
     /// Intermediate sum result.
     Sum(IntermediateSum),
+    /// Intermediate weighted average result.
+    WeightedAvg(IntermediateWeightedAvg),
+    /// Intermediate top metrics result.
+    TopMetrics(IntermediateTopMetrics),
 }
 
 impl IntermediateMetricResult {
-    fn into_final_metric_result(self, req: &Aggregation) -> MetricResult {
-        match self {
-            IntermediateMetricResult::Average(intermediate_avg) => {
-                MetricResult::Average(intermediate_avg.finalize().into())
-            }
+    fn into_final_metric_result(self, req: &Aggregation) -> crate::Result<MetricResult> {
+        let res = match self {
+            IntermediateMetricResult::Average(intermediate_avg) => MetricResult::Average(
+                intermediate_avg
+                    .into_final_result(req.agg.as_average().expect("unexpected metric type")),
+            ),
             IntermediateMetricResult::Count(intermediate_count) => {
                 MetricResult::Count(intermediate_count.finalize().into())
             }
             IntermediateMetricResult::Max(intermediate_max) => {
-                MetricResult::Max(intermediate_max.finalize().into())
+                MetricResult::Max(intermediate_max.into_final_result())
             }
             IntermediateMetricResult::Min(intermediate_min) => {
-                MetricResult::Min(intermediate_min.finalize().into())
+                MetricResult::Min(intermediate_min.into_final_result())
             }
             IntermediateMetricResult::Stats(intermediate_stats) => {
                 MetricResult::Stats(intermediate_stats.finalize())
             }
-            IntermediateMetricResult::Sum(intermediate_sum) => {
-                MetricResult::Sum(intermediate_sum.finalize().into())
+            IntermediateMetricResult::ExtendedStats(intermediate_extended_stats) => {
+                MetricResult::ExtendedStats(
+                    intermediate_extended_stats
+                        .finalize(req.agg.as_extended_stats().expect("unexpected metric type")),
+                )
             }
+            IntermediateMetricResult::Sum(intermediate_sum) => MetricResult::Sum(
+                intermediate_sum
+                    .into_final_result(req.agg.as_sum().expect("unexpected metric type"))?,
+            ),
             IntermediateMetricResult::Percentiles(percentiles) => MetricResult::Percentiles(
                 percentiles
                     .into_final_result(req.agg.as_percentile().expect("unexpected metric type")),
             ),
-        }
+            IntermediateMetricResult::WeightedAvg(intermediate_weighted_avg) => {
+                MetricResult::WeightedAvg(intermediate_weighted_avg.finalize().into())
+            }
+            IntermediateMetricResult::TopMetrics(intermediate_top_metrics) => {
+                MetricResult::TopMetrics(
+                    intermediate_top_metrics.into_final_result(
+                        req.agg.as_top_metrics().expect("unexpected metric type"),
+                    ),
+                )
+            }
+        };
+        Ok(res)
     }
 
     fn merge_fruits(&mut self, other: IntermediateMetricResult) -> crate::Result<()> {
@@ -315,6 +360,12 @@ impl IntermediateMetricResult {
             ) => {
                 stats_left.merge_fruits(stats_right);
             }
+            (
+                IntermediateMetricResult::ExtendedStats(extended_stats_left),
+                IntermediateMetricResult::ExtendedStats(extended_stats_right),
+            ) => {
+                extended_stats_left.merge_fruits(extended_stats_right);
+            }
             (IntermediateMetricResult::Sum(sum_left), IntermediateMetricResult::Sum(sum_right)) => {
                 sum_left.merge_fruits(sum_right);
             }
@@ -324,6 +375,18 @@ impl IntermediateMetricResult {
             ) => {
                 left.merge_fruits(right)?;
             }
+            (
+                IntermediateMetricResult::WeightedAvg(left),
+                IntermediateMetricResult::WeightedAvg(right),
+            ) => {
+                left.merge_fruits(right);
+            }
+            (
+                IntermediateMetricResult::TopMetrics(left),
+                IntermediateMetricResult::TopMetrics(right),
+            ) => {
+                left.merge_fruits(right);
+            }
             _ => {
                 panic!("incompatible fruit types in tree or missing merge_fruits handler");
             }
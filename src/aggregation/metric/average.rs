@@ -2,7 +2,7 @@ use std::fmt::Debug;
 
 use serde::{Deserialize, Serialize};
 
-use super::{IntermediateStats, SegmentStatsCollector};
+use super::{IntermediateStats, SegmentStatsCollector, SingleMetricResult};
 
 /// A single-value metric aggregation that computes the average of numeric values that are
 /// extracted from the aggregated documents.
@@ -26,6 +26,11 @@ pub struct AverageAggregation {
     /// { "field": "my_numbers", "missing": "10.0" }
     #[serde(default)]
     pub missing: Option<f64>,
+    /// If set to true, the result also contains the `sum` and the `count` the average is
+    /// computed from, so that averages can be combined later on. Defaults to false.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub include_sum_and_count: bool,
 }
 
 impl AverageAggregation {
@@ -34,6 +39,7 @@ impl AverageAggregation {
         Self {
             field: field_name,
             missing: None,
+            include_sum_and_count: false,
         }
     }
     /// Returns the field name the aggregation is computed on.
@@ -64,4 +70,46 @@ impl IntermediateAverage {
     pub fn finalize(&self) -> Option<f64> {
         self.stats.finalize().avg
     }
+    /// Computes the final average result, including the sum and the count if requested.
+    pub fn into_final_result(self, req: &AverageAggregation) -> SingleMetricResult {
+        let stats = self.stats.finalize();
+        if req.include_sum_and_count {
+            SingleMetricResult {
+                sum: Some(stats.sum),
+                count: Some(stats.count),
+                ..stats.avg.into()
+            }
+        } else {
+            stats.avg.into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::aggregation::agg_req::Aggregations;
+    use crate::aggregation::tests::{exec_request_with_query, get_test_index_2_segments};
+
+    #[test]
+    fn test_aggregation_avg_include_sum_and_count() -> crate::Result<()> {
+        let index = get_test_index_2_segments(false)?;
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "avg": { "avg": { "field": "score" } },
+            "avg_with_partials": {
+                "avg": { "field": "score", "include_sum_and_count": true }
+            }
+        }))
+        .unwrap();
+
+        let res = exec_request_with_query(agg_req, &index, Some(("text", "cool")))?;
+        assert_eq!(res["avg"], json!({ "value": 12.142857142857142 }));
+        assert_eq!(
+            res["avg_with_partials"],
+            json!({ "value": 12.142857142857142, "sum": 85.0, "count": 7 })
+        );
+
+        Ok(())
+    }
 }
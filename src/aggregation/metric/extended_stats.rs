@@ -0,0 +1,537 @@
+use std::fmt::Debug;
+
+use columnar::ColumnType;
+use serde::{Deserialize, Serialize};
+
+use crate::aggregation::agg_req_with_accessor::{
+    AggregationWithAccessor, AggregationsWithAccessor,
+};
+use crate::aggregation::intermediate_agg_result::{
+    IntermediateAggregationResult, IntermediateAggregationResults, IntermediateMetricResult,
+};
+use crate::aggregation::segment_agg_result::SegmentAggregationCollector;
+use crate::aggregation::{f64_from_fastfield_u64, f64_to_fastfield_u64, AggregationError};
+use crate::{DocId, TantivyError};
+
+/// A multi-value metric aggregation that computes the statistics of
+/// [`StatsAggregation`](super::StatsAggregation), plus the sum of squares, the variance and the
+/// standard deviation of numeric values that are extracted from the aggregated documents.
+/// See [`ExtendedStats`] for returned statistics.
+///
+/// # JSON Format
+/// ```json
+/// {
+///     "extended_stats": {
+///         "field": "score",
+///         "sigma": 3.0
+///     }
+///  }
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExtendedStatsAggregation {
+    /// The field name to compute the stats on.
+    pub field: String,
+    /// The missing parameter defines how documents that are missing a value should be treated.
+    /// By default they will be ignored but it is also possible to treat them as if they had a
+    /// value. Examples in JSON format:
+    /// { "field": "my_numbers", "missing": "10.0" }
+    #[serde(default)]
+    pub missing: Option<f64>,
+    /// The number of standard deviations above and below the mean used to compute
+    /// `std_deviation_bounds`. Defaults to 2.0.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sigma: Option<f64>,
+}
+
+impl ExtendedStatsAggregation {
+    /// Creates a new [`ExtendedStatsAggregation`] instance from a field name.
+    pub fn from_field_name(field_name: String) -> Self {
+        ExtendedStatsAggregation {
+            field: field_name,
+            missing: None,
+            sigma: None,
+        }
+    }
+    /// Returns the field name the aggregation is computed on.
+    pub fn field_name(&self) -> &str {
+        &self.field
+    }
+
+    pub(crate) fn validate(&self) -> crate::Result<()> {
+        if let Some(sigma) = self.sigma {
+            if sigma.is_nan() || sigma < 0.0 {
+                return Err(TantivyError::AggregationError(
+                    AggregationError::InvalidRequest(format!(
+                        "sigma of extended_stats must be positive, got {sigma}"
+                    )),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// ExtendedStats contains a collection of statistics.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExtendedStats {
+    /// The number of documents.
+    pub count: u64,
+    /// The sum of the fast field values.
+    pub sum: f64,
+    /// The min value of the fast field values.
+    pub min: Option<f64>,
+    /// The max value of the fast field values.
+    pub max: Option<f64>,
+    /// The average of the fast field values. `None` if count equals zero.
+    pub avg: Option<f64>,
+    /// The sum of the squares of the fast field values. `None` if count equals zero.
+    pub sum_of_squares: Option<f64>,
+    /// The population variance of the fast field values. `None` if count equals zero.
+    pub variance: Option<f64>,
+    /// The population standard deviation of the fast field values. `None` if count equals zero.
+    pub std_deviation: Option<f64>,
+    /// The average plus/minus `sigma` standard deviations. `None` if count equals zero.
+    pub std_deviation_bounds: Option<StdDeviationBounds>,
+}
+
+/// The bounds of the interval of `sigma` standard deviations around the average.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StdDeviationBounds {
+    /// The average plus `sigma` standard deviations.
+    pub upper: f64,
+    /// The average minus `sigma` standard deviations.
+    pub lower: f64,
+}
+
+impl ExtendedStats {
+    pub(crate) fn get_value(&self, agg_property: &str) -> crate::Result<Option<f64>> {
+        match agg_property {
+            "count" => Ok(Some(self.count as f64)),
+            "sum" => Ok(Some(self.sum)),
+            "min" => Ok(self.min),
+            "max" => Ok(self.max),
+            "avg" => Ok(self.avg),
+            "sum_of_squares" => Ok(self.sum_of_squares),
+            "variance" => Ok(self.variance),
+            "std_deviation" => Ok(self.std_deviation),
+            "std_deviation_bounds.upper" => Ok(self
+                .std_deviation_bounds
+                .as_ref()
+                .map(|bounds| bounds.upper)),
+            "std_deviation_bounds.lower" => Ok(self
+                .std_deviation_bounds
+                .as_ref()
+                .map(|bounds| bounds.lower)),
+            _ => Err(TantivyError::InvalidArgument(format!(
+                "Unknown property {agg_property} on extended_stats metric aggregation"
+            ))),
+        }
+    }
+}
+
+/// Intermediate result of the extended stats aggregation that can be combined with other
+/// intermediate results.
+///
+/// The variance is tracked with Welford's online algorithm, and intermediate results are merged
+/// with the parallel variant of the algorithm, which avoids the catastrophic cancellation of
+/// computing it from the sum of squares.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IntermediateExtendedStats {
+    /// The number of extracted values.
+    count: u64,
+    /// The sum of the extracted values.
+    sum: f64,
+    /// The min value.
+    min: f64,
+    /// The max value.
+    max: f64,
+    /// The running mean of the extracted values.
+    mean: f64,
+    /// The sum of the squared differences to the mean.
+    m2: f64,
+}
+
+impl Default for IntermediateExtendedStats {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            sum: 0.0,
+            min: f64::MAX,
+            max: f64::MIN,
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+}
+
+impl IntermediateExtendedStats {
+    /// Merges the other extended stats intermediate result into self.
+    pub fn merge_fruits(&mut self, other: IntermediateExtendedStats) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = other;
+            return;
+        }
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        self.mean += delta * other.count as f64 / count as f64;
+        self.m2 +=
+            other.m2 + delta * delta * (self.count as f64 * other.count as f64) / count as f64;
+        self.count = count;
+        self.sum += other.sum;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    /// Computes the final extended stats value.
+    pub fn finalize(&self, req: &ExtendedStatsAggregation) -> ExtendedStats {
+        if self.count == 0 {
+            return ExtendedStats {
+                count: 0,
+                sum: self.sum,
+                min: None,
+                max: None,
+                avg: None,
+                sum_of_squares: None,
+                variance: None,
+                std_deviation: None,
+                std_deviation_bounds: None,
+            };
+        }
+        let sigma = req.sigma.unwrap_or(2.0);
+        let variance = self.m2 / self.count as f64;
+        let std_deviation = variance.sqrt();
+        ExtendedStats {
+            count: self.count,
+            sum: self.sum,
+            min: Some(self.min),
+            max: Some(self.max),
+            avg: Some(self.mean),
+            sum_of_squares: Some(self.m2 + self.count as f64 * self.mean * self.mean),
+            variance: Some(variance),
+            std_deviation: Some(std_deviation),
+            std_deviation_bounds: Some(StdDeviationBounds {
+                upper: self.mean + sigma * std_deviation,
+                lower: self.mean - sigma * std_deviation,
+            }),
+        }
+    }
+
+    #[inline]
+    fn collect(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct SegmentExtendedStatsCollector {
+    missing: Option<u64>,
+    field_type: ColumnType,
+    pub(crate) extended_stats: IntermediateExtendedStats,
+    pub(crate) accessor_idx: usize,
+}
+
+impl SegmentExtendedStatsCollector {
+    pub fn from_req_and_validate(
+        req: &ExtendedStatsAggregation,
+        field_type: ColumnType,
+        accessor_idx: usize,
+    ) -> crate::Result<Self> {
+        req.validate()?;
+        let missing = req
+            .missing
+            .and_then(|val| f64_to_fastfield_u64(val, &field_type));
+        Ok(Self {
+            missing,
+            field_type,
+            extended_stats: IntermediateExtendedStats::default(),
+            accessor_idx,
+        })
+    }
+    #[inline]
+    pub(crate) fn collect_block_with_field(
+        &mut self,
+        docs: &[DocId],
+        agg_accessor: &mut AggregationWithAccessor,
+    ) {
+        if let Some(missing) = self.missing.as_ref() {
+            agg_accessor.column_block_accessor.fetch_block_with_missing(
+                docs,
+                &agg_accessor.accessor,
+                *missing,
+            );
+        } else {
+            agg_accessor
+                .column_block_accessor
+                .fetch_block(docs, &agg_accessor.accessor);
+        }
+        for val in agg_accessor.column_block_accessor.iter_vals() {
+            let val1 = f64_from_fastfield_u64(val, &self.field_type);
+            self.extended_stats.collect(val1);
+        }
+    }
+}
+
+impl SegmentAggregationCollector for SegmentExtendedStatsCollector {
+    #[inline]
+    fn add_intermediate_aggregation_result(
+        self: Box<Self>,
+        agg_with_accessor: &AggregationsWithAccessor,
+        results: &mut IntermediateAggregationResults,
+    ) -> crate::Result<()> {
+        let name = agg_with_accessor.aggs.keys[self.accessor_idx].to_string();
+        let intermediate_metric_result =
+            IntermediateMetricResult::ExtendedStats(self.extended_stats);
+
+        results.push(
+            name,
+            IntermediateAggregationResult::Metric(intermediate_metric_result),
+        )?;
+
+        Ok(())
+    }
+
+    #[inline]
+    fn collect(
+        &mut self,
+        doc: crate::DocId,
+        agg_with_accessor: &mut AggregationsWithAccessor,
+    ) -> crate::Result<()> {
+        let field = &agg_with_accessor.aggs.values[self.accessor_idx].accessor;
+        let mut has_val = false;
+        for val in field.values_for_doc(doc) {
+            let val1 = f64_from_fastfield_u64(val, &self.field_type);
+            self.extended_stats.collect(val1);
+            has_val = true;
+        }
+        if !has_val {
+            if let Some(missing) = self.missing {
+                self.extended_stats
+                    .collect(f64_from_fastfield_u64(missing, &self.field_type));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn collect_block(
+        &mut self,
+        docs: &[crate::DocId],
+        agg_with_accessor: &mut AggregationsWithAccessor,
+    ) -> crate::Result<()> {
+        let field = &mut agg_with_accessor.aggs.values[self.accessor_idx];
+        self.collect_block_with_field(docs, field);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use serde_json::Value;
+
+    use crate::aggregation::agg_req::Aggregations;
+    use crate::aggregation::tests::{
+        exec_request_with_query, get_test_index_from_values, get_test_index_from_values_and_terms,
+    };
+    use crate::aggregation::AggregationError;
+    use crate::TantivyError;
+
+    fn assert_close(actual: &Value, expected: f64) {
+        let actual = actual.as_f64().unwrap();
+        assert!(
+            (actual - expected).abs() <= expected.abs() * 1e-6,
+            "{actual} != {expected}"
+        );
+    }
+
+    #[test]
+    fn test_aggregation_extended_stats_empty_index() -> crate::Result<()> {
+        let index = get_test_index_from_values(false, &[])?;
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "extended_stats": {
+                "extended_stats": {
+                    "field": "score",
+                },
+            }
+        }))
+        .unwrap();
+
+        let res = exec_request_with_query(agg_req, &index, None)?;
+        assert_eq!(
+            res["extended_stats"],
+            json!({
+                "avg": Value::Null,
+                "count": 0,
+                "max": Value::Null,
+                "min": Value::Null,
+                "std_deviation": Value::Null,
+                "std_deviation_bounds": Value::Null,
+                "sum": 0.0,
+                "sum_of_squares": Value::Null,
+                "variance": Value::Null,
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_aggregation_extended_stats_simple() -> crate::Result<()> {
+        let index = get_test_index_from_values(false, &[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0])?;
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "extended_stats": {
+                "extended_stats": {
+                    "field": "score",
+                    "sigma": 1.0
+                },
+            }
+        }))
+        .unwrap();
+
+        let res = exec_request_with_query(agg_req, &index, None)?;
+        let extended_stats = &res["extended_stats"];
+        assert_eq!(extended_stats["count"], 8);
+        assert_eq!(extended_stats["sum"], 40.0);
+        assert_eq!(extended_stats["min"], 2.0);
+        assert_eq!(extended_stats["max"], 9.0);
+        assert_close(&extended_stats["avg"], 5.0);
+        assert_close(&extended_stats["sum_of_squares"], 232.0);
+        assert_close(&extended_stats["variance"], 4.0);
+        assert_close(&extended_stats["std_deviation"], 2.0);
+        assert_close(&extended_stats["std_deviation_bounds"]["upper"], 7.0);
+        assert_close(&extended_stats["std_deviation_bounds"]["lower"], 3.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_aggregation_extended_stats_order_by_std_deviation_bounds() -> crate::Result<()> {
+        let index = get_test_index_from_values_and_terms(
+            false,
+            &[vec![
+                (1.0, "narrow".to_string()),
+                (3.0, "narrow".to_string()),
+                (0.0, "wide".to_string()),
+                (10.0, "wide".to_string()),
+            ]],
+        )?;
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "terms": {
+                "terms": {
+                    "field": "string_id",
+                    "order": { "stats.std_deviation_bounds.lower": "asc" }
+                },
+                "aggs": {
+                    "stats": { "extended_stats": { "field": "score" } }
+                }
+            }
+        }))
+        .unwrap();
+        let res = exec_request_with_query(agg_req, &index, None)?;
+        assert_eq!(res["terms"]["buckets"][0]["key"], "wide");
+        assert_eq!(res["terms"]["buckets"][1]["key"], "narrow");
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "terms": {
+                "terms": {
+                    "field": "string_id",
+                    "order": { "stats.std_deviation_bounds.upper": "asc" }
+                },
+                "aggs": {
+                    "stats": { "extended_stats": { "field": "score" } }
+                }
+            }
+        }))
+        .unwrap();
+        let res = exec_request_with_query(agg_req, &index, None)?;
+        assert_eq!(res["terms"]["buckets"][0]["key"], "narrow");
+        assert_eq!(res["terms"]["buckets"][1]["key"], "wide");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_aggregation_extended_stats_invalid_sigma() -> crate::Result<()> {
+        let index = get_test_index_from_values(false, &[1.0])?;
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "extended_stats": {
+                "extended_stats": {
+                    "field": "score",
+                    "sigma": -1.0
+                },
+            }
+        }))
+        .unwrap();
+
+        let res = exec_request_with_query(agg_req, &index, None);
+        assert!(matches!(
+            res,
+            Err(TantivyError::AggregationError(
+                AggregationError::InvalidRequest(_)
+            ))
+        ));
+
+        Ok(())
+    }
+
+    fn test_aggregation_extended_stats_against_reference(merge_segments: bool) {
+        // Large values with a small spread, where computing the variance from the sum of
+        // squares loses most of its precision.
+        let values: Vec<f64> = (0..3_000)
+            .map(|i| 1_000_000_000.0 + ((i * 7919) % 1_000) as f64 * 0.25)
+            .collect();
+        let segment_and_values: Vec<Vec<(f64, String)>> = values
+            .chunks(1_000)
+            .map(|chunk| chunk.iter().map(|val| (*val, val.to_string())).collect())
+            .collect();
+        let index =
+            get_test_index_from_values_and_terms(merge_segments, &segment_and_values).unwrap();
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "extended_stats": {
+                "extended_stats": {
+                    "field": "score_f64",
+                },
+            }
+        }))
+        .unwrap();
+        let res = exec_request_with_query(agg_req, &index, None).unwrap();
+
+        let count = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / count;
+        let variance = values
+            .iter()
+            .map(|val| (val - mean) * (val - mean))
+            .sum::<f64>()
+            / count;
+        assert_eq!(res["extended_stats"]["count"], 3_000);
+        assert_close(&res["extended_stats"]["avg"], mean);
+        assert_close(&res["extended_stats"]["variance"], variance);
+        assert_close(&res["extended_stats"]["std_deviation"], variance.sqrt());
+    }
+
+    #[test]
+    fn test_aggregation_extended_stats_against_reference_single_segment() {
+        test_aggregation_extended_stats_against_reference(true);
+    }
+
+    #[test]
+    fn test_aggregation_extended_stats_against_reference_multi_segment() {
+        test_aggregation_extended_stats_against_reference(false);
+    }
+}
@@ -2,7 +2,8 @@ use std::fmt::Debug;
 
 use serde::{Deserialize, Serialize};
 
-use super::{IntermediateStats, SegmentStatsCollector};
+use super::{IntermediateStats, SegmentStatsCollector, SingleMetricResult};
+use crate::DocAddress;
 
 /// A single-value metric aggregation that computes the maximum of numeric values that are
 /// extracted from the aggregated documents.
@@ -26,6 +27,11 @@ pub struct MaxAggregation {
     /// { "field": "my_numbers", "missing": "10.0" }
     #[serde(default)]
     pub missing: Option<f64>,
+    /// If set to true, the result also contains the `DocAddress` of the document holding the
+    /// maximum value. On ties, the smallest `DocAddress` is returned. Defaults to false.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub include_doc_address: bool,
 }
 
 impl MaxAggregation {
@@ -34,6 +40,7 @@ impl MaxAggregation {
         Self {
             field: field_name,
             missing: None,
+            include_doc_address: false,
         }
     }
     /// Returns the field name the aggregation is computed on.
@@ -47,31 +54,185 @@ impl MaxAggregation {
 #[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct IntermediateMax {
     stats: IntermediateStats,
+    #[serde(default)]
+    doc: Option<DocAddress>,
 }
 
 impl IntermediateMax {
     /// Creates a new [`IntermediateMax`] instance from a [`SegmentStatsCollector`].
     pub(crate) fn from_collector(collector: SegmentStatsCollector) -> Self {
+        let doc = collector
+            .segment_ordinal
+            .zip(collector.extremum_doc)
+            .map(|(segment_ord, doc_id)| DocAddress::new(segment_ord, doc_id));
         Self {
             stats: collector.stats,
+            doc,
         }
     }
     /// Merges the other intermediate result into self.
     pub fn merge_fruits(&mut self, other: IntermediateMax) {
+        match (self.finalize(), other.finalize()) {
+            (_, None) => {}
+            (None, Some(_)) => self.doc = other.doc,
+            (Some(val), Some(other_val)) => {
+                let other_doc_is_smaller = match (self.doc, other.doc) {
+                    (Some(doc), Some(other_doc)) => other_doc < doc,
+                    (doc, other_doc) => doc.is_none() && other_doc.is_some(),
+                };
+                if other_val > val || (other_val == val && other_doc_is_smaller) {
+                    self.doc = other.doc;
+                }
+            }
+        }
         self.stats.merge_fruits(other.stats);
     }
     /// Computes the final maximum value.
     pub fn finalize(&self) -> Option<f64> {
         self.stats.finalize().max
     }
+    /// Computes the final maximum result, including the `DocAddress` if it was requested.
+    pub fn into_final_result(self) -> SingleMetricResult {
+        SingleMetricResult {
+            doc_address: self.doc,
+            ..self.finalize().into()
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::aggregation::agg_req::Aggregations;
+    use crate::aggregation::agg_result::{AggregationResult, AggregationResults, MetricResult};
     use crate::aggregation::tests::exec_request_with_query;
+    use crate::aggregation::AggregationCollector;
+    use crate::query::AllQuery;
     use crate::schema::{Schema, FAST};
-    use crate::Index;
+    use crate::{DocAddress, Index};
+
+    #[test]
+    fn test_max_agg_with_doc_address() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let price = schema_builder.add_f64_field("price", FAST);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(price => 3.0f64))?;
+        index_writer.add_document(doc!(price => 7.0f64))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!())?;
+        index_writer.add_document(doc!(price => 1.0f64))?;
+        index_writer.add_document(doc!(price => 12.5f64))?;
+        index_writer.add_document(doc!(price => 4.0f64))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(price => 12.0f64))?;
+        index_writer.commit()?;
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "max_price": {
+                "max": { "field": "price", "include_doc_address": true }
+            },
+            "min_price": {
+                "min": { "field": "price", "include_doc_address": true }
+            },
+            "max_price_no_doc": {
+                "max": { "field": "price" }
+            }
+        }))
+        .unwrap();
+
+        let collector = AggregationCollector::from_aggs(agg_req, Default::default());
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 3);
+        let agg_res: AggregationResults = searcher.search(&AllQuery, &collector)?;
+
+        let get_result = |name: &str| match agg_res.0.get(name) {
+            Some(AggregationResult::MetricResult(MetricResult::Max(result)))
+            | Some(AggregationResult::MetricResult(MetricResult::Min(result))) => result.clone(),
+            _ => panic!("unexpected result type"),
+        };
+        let price_of = |name: &str| {
+            let doc_address = get_result(name).doc_address.unwrap();
+            searcher
+                .segment_reader(doc_address.segment_ord)
+                .fast_fields()
+                .f64("price")
+                .unwrap()
+                .first(doc_address.doc_id)
+        };
+
+        assert_eq!(get_result("max_price").value, Some(12.5));
+        assert_eq!(price_of("max_price"), Some(12.5));
+        assert_eq!(get_result("min_price").value, Some(1.0));
+        assert_eq!(price_of("min_price"), Some(1.0));
+        assert_eq!(get_result("max_price_no_doc").value, Some(12.5));
+        assert_eq!(get_result("max_price_no_doc").doc_address, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_agg_with_doc_address_tie_across_segments() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let price = schema_builder.add_f64_field("price", FAST);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(price => 2.0f64))?;
+        index_writer.add_document(doc!(price => 9.0f64))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(price => 9.0f64))?;
+        index_writer.add_document(doc!(price => 2.0f64))?;
+        index_writer.commit()?;
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "max_price": {
+                "max": { "field": "price", "include_doc_address": true }
+            },
+            "min_price": {
+                "min": { "field": "price", "include_doc_address": true }
+            }
+        }))
+        .unwrap();
+
+        let collector = AggregationCollector::from_aggs(agg_req, Default::default());
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 2);
+        let agg_res: AggregationResults = searcher.search(&AllQuery, &collector)?;
+
+        let get_result = |name: &str| match agg_res.0.get(name) {
+            Some(AggregationResult::MetricResult(MetricResult::Max(result)))
+            | Some(AggregationResult::MetricResult(MetricResult::Min(result))) => result.clone(),
+            _ => panic!("unexpected result type"),
+        };
+        // Both segments hold the min and the max value, the smallest `DocAddress` wins.
+        let smallest_doc_address_with_price = |value: f64| {
+            searcher
+                .segment_readers()
+                .iter()
+                .enumerate()
+                .flat_map(|(segment_ord, segment_reader)| {
+                    let prices = segment_reader.fast_fields().f64("price").unwrap();
+                    (0..segment_reader.max_doc())
+                        .filter(move |&doc_id| prices.first(doc_id) == Some(value))
+                        .map(move |doc_id| DocAddress::new(segment_ord as u32, doc_id))
+                })
+                .min()
+        };
+
+        assert_eq!(get_result("max_price").value, Some(9.0));
+        assert_eq!(
+            get_result("max_price").doc_address,
+            smallest_doc_address_with_price(9.0)
+        );
+        assert_eq!(get_result("min_price").value, Some(2.0));
+        assert_eq!(
+            get_result("min_price").doc_address,
+            smallest_doc_address_with_price(2.0)
+        );
+
+        Ok(())
+    }
 
     #[test]
     fn test_max_agg_with_missing() -> crate::Result<()> {
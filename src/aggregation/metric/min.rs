@@ -2,7 +2,8 @@ use std::fmt::Debug;
 
 use serde::{Deserialize, Serialize};
 
-use super::{IntermediateStats, SegmentStatsCollector};
+use super::{IntermediateStats, SegmentStatsCollector, SingleMetricResult};
+use crate::DocAddress;
 
 /// A single-value metric aggregation that computes the minimum of numeric values that are
 /// extracted from the aggregated documents.
@@ -26,6 +27,11 @@ pub struct MinAggregation {
     /// { "field": "my_numbers", "missing": "10.0" }
     #[serde(default)]
     pub missing: Option<f64>,
+    /// If set to true, the result also contains the `DocAddress` of the document holding the
+    /// minimum value. On ties, the smallest `DocAddress` is returned. Defaults to false.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub include_doc_address: bool,
 }
 
 impl MinAggregation {
@@ -34,6 +40,7 @@ impl MinAggregation {
         Self {
             field: field_name,
             missing: None,
+            include_doc_address: false,
         }
     }
     /// Returns the field name the aggregation is computed on.
@@ -47,21 +54,48 @@ impl MinAggregation {
 #[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct IntermediateMin {
     stats: IntermediateStats,
+    #[serde(default)]
+    doc: Option<DocAddress>,
 }
 
 impl IntermediateMin {
     /// Creates a new [`IntermediateMin`] instance from a [`SegmentStatsCollector`].
     pub(crate) fn from_collector(collector: SegmentStatsCollector) -> Self {
+        let doc = collector
+            .segment_ordinal
+            .zip(collector.extremum_doc)
+            .map(|(segment_ord, doc_id)| DocAddress::new(segment_ord, doc_id));
         Self {
             stats: collector.stats,
+            doc,
         }
     }
     /// Merges the other intermediate result into self.
     pub fn merge_fruits(&mut self, other: IntermediateMin) {
+        match (self.finalize(), other.finalize()) {
+            (_, None) => {}
+            (None, Some(_)) => self.doc = other.doc,
+            (Some(val), Some(other_val)) => {
+                let other_doc_is_smaller = match (self.doc, other.doc) {
+                    (Some(doc), Some(other_doc)) => other_doc < doc,
+                    (doc, other_doc) => doc.is_none() && other_doc.is_some(),
+                };
+                if other_val < val || (other_val == val && other_doc_is_smaller) {
+                    self.doc = other.doc;
+                }
+            }
+        }
         self.stats.merge_fruits(other.stats);
     }
     /// Computes the final minimum value.
     pub fn finalize(&self) -> Option<f64> {
         self.stats.finalize().min
     }
+    /// Computes the final minimum result, including the `DocAddress` if it was requested.
+    pub fn into_final_result(self) -> SingleMetricResult {
+        SingleMetricResult {
+            doc_address: self.doc,
+            ..self.finalize().into()
+        }
+    }
 }
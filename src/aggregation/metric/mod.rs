@@ -10,21 +10,28 @@
 //! ## Supported Metric Aggregations
 //! - [Average](AverageAggregation)
 //! - [Stats](StatsAggregation)
+//! - [ExtendedStats](ExtendedStatsAggregation)
 //! - [Min](MinAggregation)
 //! - [Max](MaxAggregation)
 //! - [Sum](SumAggregation)
 //! - [Count](CountAggregation)
 //! - [Percentiles](PercentilesAggregationReq)
+//! - [WeightedAvg](WeightedAvgAggregation)
+//! - [TopMetrics](TopMetricsAggregation)
 
 mod average;
 mod count;
+mod extended_stats;
 mod max;
 mod min;
 mod percentiles;
 mod stats;
 mod sum;
+mod top_metrics;
+mod weighted_avg;
 pub use average::*;
 pub use count::*;
+pub use extended_stats::*;
 pub use max::*;
 pub use min::*;
 pub use percentiles::*;
@@ -32,6 +39,10 @@ use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 pub use stats::*;
 pub use sum::*;
+pub use top_metrics::*;
+pub use weighted_avg::*;
+
+use crate::DocAddress;
 
 /// Single-metric aggregations use this common result structure.
 ///
@@ -40,17 +51,46 @@ pub use sum::*;
 pub struct SingleMetricResult {
     /// The value of the single value metric.
     pub value: Option<f64>,
+    /// The sum of the values the value is computed from.
+    ///
+    /// Only set by the average metric aggregation if requested with `include_sum_and_count`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sum: Option<f64>,
+    /// The number of values the value is computed from.
+    ///
+    /// Only set by the average metric aggregation if requested with `include_sum_and_count`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    /// The address of the document holding the value.
+    ///
+    /// Only set by the min and max metric aggregations if requested with
+    /// `include_doc_address`, and if there was at least one value.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc_address: Option<DocAddress>,
 }
 
 impl From<f64> for SingleMetricResult {
     fn from(value: f64) -> Self {
-        Self { value: Some(value) }
+        Self {
+            value: Some(value),
+            sum: None,
+            count: None,
+            doc_address: None,
+        }
     }
 }
 
 impl From<Option<f64>> for SingleMetricResult {
     fn from(value: Option<f64>) -> Self {
-        Self { value }
+        Self {
+            value,
+            sum: None,
+            count: None,
+            doc_address: None,
+        }
     }
 }
 
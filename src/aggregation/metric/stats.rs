@@ -1,4 +1,4 @@
-use columnar::ColumnType;
+use columnar::{ColumnType, MonotonicallyMappableToU64};
 use serde::{Deserialize, Serialize};
 
 use super::*;
@@ -10,7 +10,7 @@ use crate::aggregation::intermediate_agg_result::{
 };
 use crate::aggregation::segment_agg_result::SegmentAggregationCollector;
 use crate::aggregation::{f64_from_fastfield_u64, f64_to_fastfield_u64};
-use crate::{DocId, TantivyError};
+use crate::{DocId, SegmentOrdinal, TantivyError};
 
 /// A multi-value metric aggregation that computes a collection of statistics on numeric values that
 /// are extracted from the aggregated documents.
@@ -163,11 +163,16 @@ pub(crate) enum SegmentStatsType {
 #[derive(Clone, Debug, PartialEq)]
 pub(crate) struct SegmentStatsCollector {
     missing: Option<u64>,
-    field_type: ColumnType,
+    pub(crate) field_type: ColumnType,
     pub(crate) collecting_for: SegmentStatsType,
     pub(crate) stats: IntermediateStats,
     pub(crate) accessor_idx: usize,
     val_cache: Vec<u64>,
+    /// Set if the doc holding the min or max value is tracked.
+    pub(crate) segment_ordinal: Option<SegmentOrdinal>,
+    pub(crate) extremum_doc: Option<DocId>,
+    /// Exact sum of the values, only tracked for sums on `u64` and `i64` columns.
+    pub(crate) exact_sum: Option<i128>,
 }
 
 impl SegmentStatsCollector {
@@ -178,6 +183,9 @@ impl SegmentStatsCollector {
         missing: Option<f64>,
     ) -> Self {
         let missing = missing.and_then(|val| f64_to_fastfield_u64(val, &field_type));
+        let exact_sum = (collecting_for == SegmentStatsType::Sum
+            && matches!(field_type, ColumnType::U64 | ColumnType::I64))
+        .then_some(0);
         Self {
             field_type,
             collecting_for,
@@ -185,6 +193,53 @@ impl SegmentStatsCollector {
             accessor_idx,
             missing,
             val_cache: Default::default(),
+            segment_ordinal: None,
+            extremum_doc: None,
+            exact_sum,
+        }
+    }
+
+    /// Tracks the doc holding the min or the max value, so that its `DocAddress` can be
+    /// reported. Only relevant when collecting for [`SegmentStatsType::Min`] or
+    /// [`SegmentStatsType::Max`].
+    pub(crate) fn with_extremum_doc(mut self, segment_ordinal: SegmentOrdinal) -> Self {
+        self.segment_ordinal = Some(segment_ordinal);
+        self
+    }
+
+    #[inline]
+    fn collect_exact_sum(&mut self, val: u64) {
+        if let Some(exact_sum) = self.exact_sum.as_mut() {
+            let val = match self.field_type {
+                ColumnType::I64 => i64::from_u64(val) as i128,
+                _ => val as i128,
+            };
+            *exact_sum = exact_sum.saturating_add(val);
+        }
+    }
+
+    #[inline]
+    fn collect_value(&mut self, doc: DocId, val: f64) {
+        if self.segment_ordinal.is_some() {
+            self.track_extremum_doc(doc, val);
+        }
+        self.stats.collect(val);
+    }
+
+    /// On ties, the doc with the smallest `DocId` is kept.
+    #[inline]
+    fn track_extremum_doc(&mut self, doc: DocId, val: f64) {
+        let (current, is_better) = match self.collecting_for {
+            SegmentStatsType::Max => (self.stats.max, val > self.stats.max),
+            SegmentStatsType::Min => (self.stats.min, val < self.stats.min),
+            _ => return,
+        };
+        let replace = match self.extremum_doc {
+            None => true,
+            Some(extremum_doc) => is_better || (val == current && doc < extremum_doc),
+        };
+        if replace {
+            self.extremum_doc = Some(doc);
         }
     }
     #[inline]
@@ -204,9 +259,18 @@ impl SegmentStatsCollector {
                 .column_block_accessor
                 .fetch_block(docs, &agg_accessor.accessor);
         }
-        for val in agg_accessor.column_block_accessor.iter_vals() {
-            let val1 = f64_from_fastfield_u64(val, &self.field_type);
-            self.stats.collect(val1);
+        if self.segment_ordinal.is_some() {
+            for (doc, val) in agg_accessor.column_block_accessor.iter_docid_vals() {
+                let val1 = f64_from_fastfield_u64(val, &self.field_type);
+                self.collect_value(doc, val1);
+                self.collect_exact_sum(val);
+            }
+        } else {
+            for val in agg_accessor.column_block_accessor.iter_vals() {
+                let val1 = f64_from_fastfield_u64(val, &self.field_type);
+                self.stats.collect(val1);
+                self.collect_exact_sum(val);
+            }
         }
     }
 }
@@ -258,17 +322,19 @@ impl SegmentAggregationCollector for SegmentStatsCollector {
             let mut has_val = false;
             for val in field.values_for_doc(doc) {
                 let val1 = f64_from_fastfield_u64(val, &self.field_type);
-                self.stats.collect(val1);
+                self.collect_value(doc, val1);
+                self.collect_exact_sum(val);
                 has_val = true;
             }
             if !has_val {
-                self.stats
-                    .collect(f64_from_fastfield_u64(missing, &self.field_type));
+                self.collect_value(doc, f64_from_fastfield_u64(missing, &self.field_type));
+                self.collect_exact_sum(missing);
             }
         } else {
             for val in field.values_for_doc(doc) {
                 let val1 = f64_from_fastfield_u64(val, &self.field_type);
-                self.stats.collect(val1);
+                self.collect_value(doc, val1);
+                self.collect_exact_sum(val);
             }
         }
 
@@ -1,13 +1,23 @@
 use std::fmt::Debug;
 
+use columnar::ColumnType;
 use serde::{Deserialize, Serialize};
 
-use super::{IntermediateStats, SegmentStatsCollector};
+use super::{IntermediateStats, SegmentStatsCollector, SingleMetricResult};
+use crate::aggregation::AggregationError;
+use crate::TantivyError;
 
 /// A single-value metric aggregation that sums up numeric values that are
 /// extracted from the aggregated documents.
 /// See [super::SingleMetricResult] for return value.
 ///
+/// Values are summed as `f64`, both within a segment and when merging the results of several
+/// segments, so sums above 2^53 are not exact on `f64` fields.
+///
+/// On `u64` and `i64` fields, the values are also summed exactly as `i128`. The result is
+/// computed from that exact sum, and the aggregation fails with
+/// [`AggregationError::SumOverflow`] if it exceeds the range of the field type.
+///
 /// # JSON Format
 /// ```json
 /// {
@@ -44,9 +54,27 @@ impl SumAggregation {
 
 /// Intermediate result of the minimum aggregation that can be combined with other intermediate
 /// results.
-#[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct IntermediateSum {
     stats: IntermediateStats,
+    /// Exact sum of the values. `None` if some values come from a column that is not `u64` or
+    /// `i64`.
+    #[serde(default)]
+    exact_sum: Option<i128>,
+    /// Whether some values come from an `i64` column, in which case the exact sum has to fit in
+    /// an `i64` rather than in a `u64`.
+    #[serde(default)]
+    has_i64_values: bool,
+}
+
+impl Default for IntermediateSum {
+    fn default() -> Self {
+        Self {
+            stats: IntermediateStats::default(),
+            exact_sum: Some(0),
+            has_i64_values: false,
+        }
+    }
 }
 
 impl IntermediateSum {
@@ -54,14 +82,119 @@ impl IntermediateSum {
     pub(crate) fn from_collector(collector: SegmentStatsCollector) -> Self {
         Self {
             stats: collector.stats,
+            exact_sum: collector.exact_sum,
+            has_i64_values: collector.field_type == ColumnType::I64,
         }
     }
     /// Merges the other intermediate result into self.
     pub fn merge_fruits(&mut self, other: IntermediateSum) {
         self.stats.merge_fruits(other.stats);
+        self.exact_sum = self
+            .exact_sum
+            .zip(other.exact_sum)
+            .map(|(left, right)| left.saturating_add(right));
+        self.has_i64_values |= other.has_i64_values;
     }
     /// Computes the final minimum value.
     pub fn finalize(&self) -> Option<f64> {
-        Some(self.stats.finalize().sum)
+        match self.exact_sum {
+            Some(exact_sum) => Some(exact_sum as f64),
+            None => Some(self.stats.finalize().sum),
+        }
+    }
+    /// Computes the final sum, checking that the exact sum fits in the range of the field type.
+    pub(crate) fn into_final_result(
+        self,
+        req: &SumAggregation,
+    ) -> crate::Result<SingleMetricResult> {
+        if let Some(exact_sum) = self.exact_sum {
+            let fits = if self.has_i64_values {
+                i64::try_from(exact_sum).is_ok()
+            } else {
+                u64::try_from(exact_sum).is_ok()
+            };
+            if !fits {
+                return Err(TantivyError::AggregationError(
+                    AggregationError::SumOverflow {
+                        field: req.field.clone(),
+                        sum: exact_sum,
+                    },
+                ));
+            }
+        }
+        Ok(self.finalize().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::aggregation::agg_req::Aggregations;
+    use crate::aggregation::tests::exec_request_with_query;
+    use crate::aggregation::AggregationError;
+    use crate::schema::{Schema, FAST};
+    use crate::{Index, TantivyError};
+
+    #[test]
+    fn test_sum_agg_overflow() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let count = schema_builder.add_u64_field("count", FAST);
+        let delta = schema_builder.add_i64_field("delta", FAST);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_for_tests()?;
+        // the sum of each segment overflows on its own
+        index_writer.add_document(doc!(count => u64::MAX, delta => i64::MIN))?;
+        index_writer.add_document(doc!(count => u64::MAX, delta => i64::MIN))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(count => u64::MAX, delta => i64::MIN))?;
+        index_writer.add_document(doc!(count => u64::MAX, delta => i64::MIN))?;
+        index_writer.commit()?;
+
+        let exec = |agg_req: serde_json::Value| {
+            let agg_req: Aggregations = serde_json::from_value(agg_req).unwrap();
+            exec_request_with_query(agg_req, &index, None)
+        };
+        let res = exec(json!({ "count_sum": { "sum": { "field": "count" } } }));
+        assert!(matches!(
+            res,
+            Err(TantivyError::AggregationError(AggregationError::SumOverflow { field, sum }))
+                if field == "count" && sum == 4 * u64::MAX as i128
+        ));
+        let res = exec(json!({ "delta_sum": { "sum": { "field": "delta" } } }));
+        assert!(matches!(
+            res,
+            Err(TantivyError::AggregationError(AggregationError::SumOverflow { field, sum }))
+                if field == "delta" && sum == 4 * i64::MIN as i128
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sum_agg_exact_integer_sum() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let count = schema_builder.add_u64_field("count", FAST);
+        let delta = schema_builder.add_i64_field("delta", FAST);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_for_tests()?;
+        // the partial sums exceed the range of the field type, the total does not
+        index_writer.add_document(doc!(count => u64::MAX - 1, delta => i64::MAX))?;
+        index_writer.add_document(doc!(count => 0u64, delta => i64::MAX))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(count => 1u64, delta => i64::MIN))?;
+        index_writer.commit()?;
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "count_sum": { "sum": { "field": "count" } },
+            "delta_sum": { "sum": { "field": "delta" } }
+        }))
+        .unwrap();
+
+        let res = exec_request_with_query(agg_req, &index, None)?;
+        assert_eq!(res["count_sum"]["value"], u64::MAX as f64);
+        assert_eq!(res["delta_sum"]["value"], (i64::MAX - 1) as f64);
+
+        Ok(())
     }
 }
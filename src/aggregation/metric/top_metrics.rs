@@ -0,0 +1,494 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use columnar::ColumnType;
+use rustc_hash::FxHashMap;
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::aggregation::agg_req_with_accessor::{
+    AggregationWithAccessor, AggregationsWithAccessor,
+};
+use crate::aggregation::bucket::Order;
+use crate::aggregation::intermediate_agg_result::{
+    IntermediateAggregationResult, IntermediateAggregationResults, IntermediateMetricResult,
+};
+use crate::aggregation::segment_agg_result::SegmentAggregationCollector;
+use crate::aggregation::{f64_from_fastfield_u64, AggregationError};
+use crate::collector::top_collector::{ComparableDoc, TopSegmentCollector};
+use crate::{DocAddress, DocId, SegmentOrdinal, TantivyError};
+
+/// A metric aggregation that selects the top `size` documents by the value of a sort field and
+/// returns the values of the requested metric fields of those documents.
+/// See [TopMetricsMetricResult] for return value.
+///
+/// Documents without a value in the sort field are skipped. Only the first value of the sort
+/// field and of the metric fields is used. A metric is `None` if the document has no value for
+/// it. Ties on the sort value are broken by ascending [`DocAddress`].
+///
+/// `metrics` accepts a single field or a list of fields. `size` defaults to 1.
+///
+/// # JSON Format
+/// ```json
+/// {
+///     "top_metrics": {
+///         "metrics": [{ "field": "price" }, { "field": "qty" }],
+///         "sort": { "timestamp": "desc" },
+///         "size": 3
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TopMetricsAggregation {
+    /// The fields whose values are returned for the top documents.
+    #[serde(deserialize_with = "deserialize_top_metrics_fields")]
+    pub metrics: Vec<TopMetricsField>,
+    /// The field and order used to select the top documents.
+    pub sort: TopMetricsSort,
+    /// The number of top documents to return.
+    #[serde(default = "default_top_metrics_size")]
+    pub size: usize,
+}
+
+fn default_top_metrics_size() -> usize {
+    1
+}
+
+/// Reference to a fast field in a [`TopMetricsAggregation`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TopMetricsField {
+    /// The fast field name.
+    pub field: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TopMetricsFields {
+    Single(TopMetricsField),
+    Multiple(Vec<TopMetricsField>),
+}
+
+fn deserialize_top_metrics_fields<'de, D>(
+    deserializer: D,
+) -> Result<Vec<TopMetricsField>, D::Error>
+where D: Deserializer<'de> {
+    Ok(match TopMetricsFields::deserialize(deserializer)? {
+        TopMetricsFields::Single(field) => vec![field],
+        TopMetricsFields::Multiple(fields) => fields,
+    })
+}
+
+/// The sort of a [`TopMetricsAggregation`], e.g. `{ "timestamp": "desc" }`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TopMetricsSort {
+    /// The fast field to sort on.
+    pub field: String,
+    /// The order of the sort. `Desc` returns the documents with the largest values.
+    pub order: Order,
+}
+
+impl Serialize for TopMetricsSort {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        let map: HashMap<String, Order> =
+            std::iter::once((self.field.to_string(), self.order)).collect();
+        map.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for TopMetricsSort {
+    fn deserialize<D>(deserializer: D) -> Result<TopMetricsSort, D::Error>
+    where D: Deserializer<'de> {
+        HashMap::<String, Order>::deserialize(deserializer).and_then(|map| {
+            if map.len() != 1 {
+                return Err(de::Error::custom(format!(
+                    "expected exactly one sort field in top_metrics, got {}",
+                    map.len()
+                )));
+            }
+            let (field, order) = map.into_iter().next().expect("map has one entry");
+            Ok(TopMetricsSort { field, order })
+        })
+    }
+}
+
+impl TopMetricsAggregation {
+    /// Returns the name of the field to sort on.
+    pub fn field_name(&self) -> &str {
+        &self.sort.field
+    }
+    /// Returns the names of the metric fields.
+    pub fn metric_field_names(&self) -> impl Iterator<Item = &str> {
+        self.metrics.iter().map(|metric| metric.field.as_str())
+    }
+
+    pub(crate) fn validate(&self) -> crate::Result<()> {
+        if self.size == 0 {
+            return Err(TantivyError::AggregationError(
+                AggregationError::InvalidRequest(
+                    "size of top_metrics must be greater than 0".to_string(),
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The result of the top metrics aggregation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TopMetricsMetricResult {
+    /// The top documents, best first.
+    pub top: Vec<TopMetricsEntry>,
+}
+
+impl TopMetricsMetricResult {
+    pub(crate) fn get_value(&self, agg_property: &str) -> crate::Result<Option<f64>> {
+        let top = match self.top.first() {
+            Some(top) => top,
+            None => return Ok(None),
+        };
+        if agg_property.is_empty() && top.metrics.len() == 1 {
+            return Ok(top.metrics.values().next().copied().flatten());
+        }
+        top.metrics.get(agg_property).copied().ok_or_else(|| {
+            TantivyError::AggregationError(AggregationError::InvalidRequest(format!(
+                "unknown metric {agg_property:?} of top_metrics"
+            )))
+        })
+    }
+}
+
+/// A document selected by the top metrics aggregation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TopMetricsEntry {
+    /// The sort value of the document.
+    pub sort: Vec<f64>,
+    /// The values of the requested metric fields, keyed by field name.
+    pub metrics: FxHashMap<String, Option<f64>>,
+}
+
+/// Intermediate result of the top metrics aggregation that can be combined with other
+/// intermediate results.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IntermediateTopMetrics {
+    order: Order,
+    size: usize,
+    /// The top documents, best first.
+    top: Vec<IntermediateTopMetricsEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct IntermediateTopMetricsEntry {
+    sort_value: f64,
+    doc: DocAddress,
+    metrics: Vec<Option<f64>>,
+}
+
+impl IntermediateTopMetrics {
+    pub(crate) fn from_req(req: &TopMetricsAggregation) -> Self {
+        Self {
+            order: req.sort.order,
+            size: req.size,
+            top: Vec::new(),
+        }
+    }
+
+    /// Merges the other intermediate result into self.
+    pub fn merge_fruits(&mut self, other: IntermediateTopMetrics) {
+        self.top.extend(other.top);
+        let order = self.order;
+        self.top.sort_by(|left, right| {
+            let left = comparable_doc(order, left.sort_value, left.doc);
+            let right = comparable_doc(order, right.sort_value, right.doc);
+            left.cmp(&right)
+        });
+        self.top.truncate(self.size);
+    }
+
+    /// Converts the intermediate result into the final result.
+    pub fn into_final_result(self, req: &TopMetricsAggregation) -> TopMetricsMetricResult {
+        let top = self
+            .top
+            .into_iter()
+            .map(|entry| TopMetricsEntry {
+                sort: vec![entry.sort_value],
+                metrics: req
+                    .metric_field_names()
+                    .map(ToString::to_string)
+                    .zip(entry.metrics)
+                    .collect(),
+            })
+            .collect();
+        TopMetricsMetricResult { top }
+    }
+}
+
+/// Returns the feature used to rank documents. [`ComparableDoc`] keeps the largest features, so
+/// ascending sorts negate the value.
+fn sort_feature(order: Order, sort_value: f64) -> f64 {
+    match order {
+        Order::Asc => -sort_value,
+        Order::Desc => sort_value,
+    }
+}
+
+/// Sorting `ComparableDoc`s puts the best document first.
+fn comparable_doc(
+    order: Order,
+    sort_value: f64,
+    doc: DocAddress,
+) -> ComparableDoc<f64, DocAddress> {
+    ComparableDoc {
+        feature: sort_feature(order, sort_value),
+        doc,
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct SegmentTopMetricsCollector {
+    field_type: ColumnType,
+    order: Order,
+    size: usize,
+    top: TopSegmentCollector<f64>,
+    accessor_idx: usize,
+}
+
+impl SegmentTopMetricsCollector {
+    pub fn from_req_and_validate(
+        req: &TopMetricsAggregation,
+        field_type: ColumnType,
+        segment_ordinal: SegmentOrdinal,
+        accessor_idx: usize,
+    ) -> crate::Result<Self> {
+        req.validate()?;
+        Ok(Self {
+            field_type,
+            order: req.sort.order,
+            size: req.size,
+            top: TopSegmentCollector::new(segment_ordinal, req.size),
+            accessor_idx,
+        })
+    }
+
+    #[inline]
+    fn collect_doc(&mut self, doc: DocId, agg_accessor: &AggregationWithAccessor) {
+        if let Some(val) = agg_accessor.accessor.first(doc) {
+            let sort_value = f64_from_fastfield_u64(val, &self.field_type);
+            self.top.collect(doc, sort_feature(self.order, sort_value));
+        }
+    }
+}
+
+impl SegmentAggregationCollector for SegmentTopMetricsCollector {
+    #[inline]
+    fn add_intermediate_aggregation_result(
+        self: Box<Self>,
+        agg_with_accessor: &AggregationsWithAccessor,
+        results: &mut IntermediateAggregationResults,
+    ) -> crate::Result<()> {
+        let name = agg_with_accessor.aggs.keys[self.accessor_idx].to_string();
+        let agg_accessor = &agg_with_accessor.aggs.values[self.accessor_idx];
+        let order = self.order;
+        let top = self
+            .top
+            .harvest()
+            .into_iter()
+            .map(|(feature, doc)| IntermediateTopMetricsEntry {
+                sort_value: sort_feature(order, feature),
+                doc,
+                metrics: agg_accessor
+                    .metric_accessors
+                    .iter()
+                    .map(|(accessor, column_type)| {
+                        accessor
+                            .first(doc.doc_id)
+                            .map(|val| f64_from_fastfield_u64(val, column_type))
+                    })
+                    .collect(),
+            })
+            .collect();
+        let intermediate_metric_result =
+            IntermediateMetricResult::TopMetrics(IntermediateTopMetrics {
+                order,
+                size: self.size,
+                top,
+            });
+
+        results.push(
+            name,
+            IntermediateAggregationResult::Metric(intermediate_metric_result),
+        )?;
+
+        Ok(())
+    }
+
+    #[inline]
+    fn collect(
+        &mut self,
+        doc: crate::DocId,
+        agg_with_accessor: &mut AggregationsWithAccessor,
+    ) -> crate::Result<()> {
+        let agg_accessor = &agg_with_accessor.aggs.values[self.accessor_idx];
+        self.collect_doc(doc, agg_accessor);
+        Ok(())
+    }
+
+    #[inline]
+    fn collect_block(
+        &mut self,
+        docs: &[crate::DocId],
+        agg_with_accessor: &mut AggregationsWithAccessor,
+    ) -> crate::Result<()> {
+        let agg_accessor = &agg_with_accessor.aggs.values[self.accessor_idx];
+        for &doc in docs {
+            self.collect_doc(doc, agg_accessor);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::aggregation::agg_req::Aggregations;
+    use crate::aggregation::tests::exec_request_with_query;
+    use crate::aggregation::AggregationError;
+    use crate::schema::{Schema, FAST};
+    use crate::{Index, TantivyError};
+
+    fn get_top_metrics_test_index(merge_segments: bool) -> crate::Result<Index> {
+        let mut schema_builder = Schema::builder();
+        let timestamp = schema_builder.add_i64_field("timestamp", FAST);
+        let price = schema_builder.add_f64_field("price", FAST);
+        let qty = schema_builder.add_u64_field("qty", FAST);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(timestamp => 3i64, price => 10.0f64, qty => 1u64))?;
+        index_writer.add_document(doc!(timestamp => -2i64, price => 20.0f64, qty => 2u64))?;
+        // missing sort value, is skipped
+        index_writer.add_document(doc!(price => 1000.0f64, qty => 1000u64))?;
+        // tie on the sort value with the first document
+        index_writer.add_document(doc!(timestamp => 3i64, price => 40.0f64, qty => 4u64))?;
+        index_writer.commit()?;
+        // missing qty
+        index_writer.add_document(doc!(timestamp => 7i64, price => 30.0f64))?;
+        index_writer.add_document(doc!(timestamp => 1i64, price => 50.0f64, qty => 5u64))?;
+        index_writer.commit()?;
+        if merge_segments {
+            let segment_ids = index.searchable_segment_ids()?;
+            index_writer.merge(&segment_ids).wait()?;
+            index_writer.wait_merging_threads()?;
+        }
+        Ok(index)
+    }
+
+    fn test_top_metrics_with_opt(merge_segments: bool) -> crate::Result<()> {
+        let index = get_top_metrics_test_index(merge_segments)?;
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "latest": {
+                "top_metrics": {
+                    "metrics": [{ "field": "price" }, { "field": "qty" }],
+                    "sort": { "timestamp": "desc" },
+                    "size": 3
+                }
+            },
+            "earliest": {
+                "top_metrics": {
+                    "metrics": { "field": "price" },
+                    "sort": { "timestamp": "asc" }
+                }
+            }
+        }))
+        .unwrap();
+
+        let res = exec_request_with_query(agg_req, &index, None)?;
+        assert_eq!(
+            res["latest"],
+            json!({
+                "top": [
+                    { "sort": [7.0], "metrics": { "price": 30.0, "qty": null } },
+                    { "sort": [3.0], "metrics": { "price": 10.0, "qty": 1.0 } },
+                    { "sort": [3.0], "metrics": { "price": 40.0, "qty": 4.0 } },
+                ]
+            })
+        );
+        assert_eq!(
+            res["earliest"],
+            json!({
+                "top": [
+                    { "sort": [-2.0], "metrics": { "price": 20.0 } },
+                ]
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_top_metrics() -> crate::Result<()> {
+        test_top_metrics_with_opt(false)
+    }
+
+    #[test]
+    fn test_top_metrics_merged_segments() -> crate::Result<()> {
+        test_top_metrics_with_opt(true)
+    }
+
+    #[test]
+    fn test_top_metrics_no_sort_value() -> crate::Result<()> {
+        let index = get_top_metrics_test_index(false)?;
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "latest": {
+                "top_metrics": {
+                    "metrics": { "field": "price" },
+                    "sort": { "does_not_exist": "desc" }
+                }
+            }
+        }))
+        .unwrap();
+
+        let res = exec_request_with_query(agg_req, &index, None)?;
+        assert_eq!(res["latest"], json!({ "top": [] }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_top_metrics_invalid_request() -> crate::Result<()> {
+        let index = get_top_metrics_test_index(false)?;
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "latest": {
+                "top_metrics": {
+                    "metrics": { "field": "price" },
+                    "sort": { "timestamp": "desc" },
+                    "size": 0
+                }
+            }
+        }))
+        .unwrap();
+
+        let res = exec_request_with_query(agg_req, &index, None);
+        assert!(matches!(
+            res,
+            Err(TantivyError::AggregationError(
+                AggregationError::InvalidRequest(_)
+            ))
+        ));
+
+        let agg_req: serde_json::Result<Aggregations> = serde_json::from_value(json!({
+            "latest": {
+                "top_metrics": {
+                    "metrics": { "field": "price" },
+                    "sort": { "timestamp": "desc", "price": "asc" }
+                }
+            }
+        }));
+        assert!(agg_req.is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,272 @@
+use std::fmt::Debug;
+
+use columnar::ColumnType;
+use serde::{Deserialize, Serialize};
+
+use crate::aggregation::agg_req_with_accessor::{
+    AggregationWithAccessor, AggregationsWithAccessor,
+};
+use crate::aggregation::f64_from_fastfield_u64;
+use crate::aggregation::intermediate_agg_result::{
+    IntermediateAggregationResult, IntermediateAggregationResults, IntermediateMetricResult,
+};
+use crate::aggregation::segment_agg_result::SegmentAggregationCollector;
+use crate::DocId;
+
+/// A single-value metric aggregation that computes the weighted average of numeric values that
+/// are extracted from the aggregated documents, i.e. `sum(value * weight) / sum(weight)`.
+/// See [super::SingleMetricResult] for return value.
+///
+/// Documents without a weight, or with a weight of zero, are skipped. Documents without a value
+/// are skipped as well. If the value field is multivalued, every value of the document is
+/// weighted with the document's weight. Only the first value of the weight field is used.
+///
+/// The result is `None` if no document contributed a weight.
+///
+/// # JSON Format
+/// ```json
+/// {
+///     "weighted_avg": {
+///         "value": { "field": "rating" },
+///         "weight": { "field": "vote_count" }
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WeightedAvgAggregation {
+    /// The field providing the values to average.
+    pub value: WeightedAvgField,
+    /// The field providing the weight of each document.
+    pub weight: WeightedAvgField,
+}
+
+/// Reference to a fast field in a [`WeightedAvgAggregation`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WeightedAvgField {
+    /// The fast field name.
+    pub field: String,
+}
+
+impl WeightedAvgAggregation {
+    /// Creates a new [`WeightedAvgAggregation`] instance from the value and weight field names.
+    pub fn from_field_names(value_field_name: String, weight_field_name: String) -> Self {
+        Self {
+            value: WeightedAvgField {
+                field: value_field_name,
+            },
+            weight: WeightedAvgField {
+                field: weight_field_name,
+            },
+        }
+    }
+    /// Returns the name of the field providing the values.
+    pub fn field_name(&self) -> &str {
+        &self.value.field
+    }
+    /// Returns the name of the field providing the weights.
+    pub fn weight_field_name(&self) -> &str {
+        &self.weight.field
+    }
+}
+
+/// Intermediate result of the weighted average aggregation that can be combined with other
+/// intermediate results.
+#[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IntermediateWeightedAvg {
+    /// The sum of `value * weight`.
+    weighted_sum: f64,
+    /// The sum of the weights.
+    weight_sum: f64,
+}
+
+impl IntermediateWeightedAvg {
+    /// Merges the other intermediate result into self.
+    pub fn merge_fruits(&mut self, other: IntermediateWeightedAvg) {
+        self.weighted_sum += other.weighted_sum;
+        self.weight_sum += other.weight_sum;
+    }
+    /// Computes the final weighted average value.
+    pub fn finalize(&self) -> Option<f64> {
+        if self.weight_sum == 0.0 {
+            None
+        } else {
+            Some(self.weighted_sum / self.weight_sum)
+        }
+    }
+
+    #[inline]
+    fn collect(&mut self, value: f64, weight: f64) {
+        self.weighted_sum += value * weight;
+        self.weight_sum += weight;
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct SegmentWeightedAvgCollector {
+    field_type: ColumnType,
+    weight_field_type: ColumnType,
+    pub(crate) weighted_avg: IntermediateWeightedAvg,
+    pub(crate) accessor_idx: usize,
+}
+
+impl SegmentWeightedAvgCollector {
+    pub fn from_req(
+        field_type: ColumnType,
+        weight_field_type: ColumnType,
+        accessor_idx: usize,
+    ) -> Self {
+        Self {
+            field_type,
+            weight_field_type,
+            weighted_avg: IntermediateWeightedAvg::default(),
+            accessor_idx,
+        }
+    }
+
+    #[inline]
+    fn collect_doc(&mut self, doc: DocId, agg_accessor: &AggregationWithAccessor) {
+        let weight = agg_accessor
+            .weight_accessor
+            .as_ref()
+            .and_then(|(weight_accessor, _)| weight_accessor.first(doc));
+        let weight = match weight {
+            Some(weight) => f64_from_fastfield_u64(weight, &self.weight_field_type),
+            None => return,
+        };
+        if weight == 0.0 {
+            return;
+        }
+        for val in agg_accessor.accessor.values_for_doc(doc) {
+            let val = f64_from_fastfield_u64(val, &self.field_type);
+            self.weighted_avg.collect(val, weight);
+        }
+    }
+}
+
+impl SegmentAggregationCollector for SegmentWeightedAvgCollector {
+    #[inline]
+    fn add_intermediate_aggregation_result(
+        self: Box<Self>,
+        agg_with_accessor: &AggregationsWithAccessor,
+        results: &mut IntermediateAggregationResults,
+    ) -> crate::Result<()> {
+        let name = agg_with_accessor.aggs.keys[self.accessor_idx].to_string();
+        let intermediate_metric_result = IntermediateMetricResult::WeightedAvg(self.weighted_avg);
+
+        results.push(
+            name,
+            IntermediateAggregationResult::Metric(intermediate_metric_result),
+        )?;
+
+        Ok(())
+    }
+
+    #[inline]
+    fn collect(
+        &mut self,
+        doc: crate::DocId,
+        agg_with_accessor: &mut AggregationsWithAccessor,
+    ) -> crate::Result<()> {
+        let agg_accessor = &agg_with_accessor.aggs.values[self.accessor_idx];
+        self.collect_doc(doc, agg_accessor);
+        Ok(())
+    }
+
+    #[inline]
+    fn collect_block(
+        &mut self,
+        docs: &[crate::DocId],
+        agg_with_accessor: &mut AggregationsWithAccessor,
+    ) -> crate::Result<()> {
+        let agg_accessor = &agg_with_accessor.aggs.values[self.accessor_idx];
+        for &doc in docs {
+            self.collect_doc(doc, agg_accessor);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::aggregation::agg_req::Aggregations;
+    use crate::aggregation::tests::exec_request_with_query;
+    use crate::schema::{Schema, FAST};
+    use crate::Index;
+
+    fn get_weighted_test_index(merge_segments: bool) -> crate::Result<Index> {
+        let mut schema_builder = Schema::builder();
+        let rating = schema_builder.add_f64_field("rating", FAST);
+        let vote_count = schema_builder.add_u64_field("vote_count", FAST);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(rating => 4.0f64, vote_count => 3u64))?;
+        index_writer.add_document(doc!(rating => 2.0f64, vote_count => 1u64))?;
+        index_writer.commit()?;
+        // zero weight, is skipped
+        index_writer.add_document(doc!(rating => 100.0f64, vote_count => 0u64))?;
+        // missing weight, is skipped
+        index_writer.add_document(doc!(rating => 100.0f64))?;
+        // missing value, is skipped
+        index_writer.add_document(doc!(vote_count => 10u64))?;
+        index_writer.add_document(doc!(rating => 1.0f64, vote_count => 4u64))?;
+        index_writer.commit()?;
+        if merge_segments {
+            let segment_ids = index.searchable_segment_ids()?;
+            index_writer.merge(&segment_ids).wait()?;
+            index_writer.wait_merging_threads()?;
+        }
+        Ok(index)
+    }
+
+    fn test_weighted_avg_with_opt(merge_segments: bool) -> crate::Result<()> {
+        let index = get_weighted_test_index(merge_segments)?;
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "weighted_rating": {
+                "weighted_avg": {
+                    "value": { "field": "rating" },
+                    "weight": { "field": "vote_count" }
+                }
+            }
+        }))
+        .unwrap();
+
+        let res = exec_request_with_query(agg_req, &index, None)?;
+        // (4*3 + 2*1 + 1*4) / (3 + 1 + 4)
+        assert_eq!(res["weighted_rating"]["value"], 2.25);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_weighted_avg() -> crate::Result<()> {
+        test_weighted_avg_with_opt(false)
+    }
+
+    #[test]
+    fn test_weighted_avg_merged_segments() -> crate::Result<()> {
+        test_weighted_avg_with_opt(true)
+    }
+
+    #[test]
+    fn test_weighted_avg_no_weight() -> crate::Result<()> {
+        let index = get_weighted_test_index(false)?;
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "weighted_rating": {
+                "weighted_avg": {
+                    "value": { "field": "rating" },
+                    "weight": { "field": "does_not_exist" }
+                }
+            }
+        }))
+        .unwrap();
+
+        let res = exec_request_with_query(agg_req, &index, None)?;
+        assert_eq!(res["weighted_rating"]["value"], serde_json::Value::Null);
+
+        Ok(())
+    }
+}
@@ -7,7 +7,8 @@
 //! - How many errors with status code 500 do we have per day?
 //! - What is the average listing price of cars grouped by color?
 //!
-//! There are two categories: [Metrics](metric) and [Buckets](bucket).
+//! There are two categories: [Metrics](metric) and [Buckets](bucket). In addition,
+//! [Pipeline](pipeline) aggregations compute their value from the output of other aggregations.
 //!
 //! ## Prerequisite
 //! Currently aggregations work only on [fast fields](`crate::fastfield`). Fast fields
@@ -44,11 +45,16 @@
 //! - [Metric](metric)
 //!     - [Average](metric::AverageAggregation)
 //!     - [Stats](metric::StatsAggregation)
+//!     - [ExtendedStats](metric::ExtendedStatsAggregation)
 //!     - [Min](metric::MinAggregation)
 //!     - [Max](metric::MaxAggregation)
 //!     - [Sum](metric::SumAggregation)
 //!     - [Count](metric::CountAggregation)
 //!     - [Percentiles](metric::PercentilesAggregationReq)
+//!     - [WeightedAvg](metric::WeightedAvgAggregation)
+//!     - [TopMetrics](metric::TopMetricsAggregation)
+//! - [Pipeline](pipeline)
+//!     - [Derivative](pipeline::DerivativeAggregation)
 //!
 //! # Example
 //! Compute the average metric, by building [`agg_req::Aggregations`], which is built from an
@@ -135,6 +141,7 @@ mod date;
 mod error;
 pub mod intermediate_agg_result;
 pub mod metric;
+pub mod pipeline;
 
 mod segment_agg_result;
 use std::collections::HashMap;
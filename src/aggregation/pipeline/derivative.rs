@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+
+/// A pipeline aggregation that computes the derivative of a metric over the buckets of its
+/// parent `histogram` or `date_histogram` aggregation, i.e. the value of the metric in a bucket
+/// minus its value in the previous bucket.
+/// See [SingleMetricResult](crate::aggregation::metric::SingleMetricResult) for return value.
+///
+/// The metric is referenced with `buckets_path`, which is either the name of a sibling
+/// single-value metric aggregation, e.g. `"sales"`, a property of a sibling multi-value metric
+/// aggregation, e.g. `"sales_stats.max"`, or `"_count"` for the document count of the bucket.
+/// It can also reference another derivative, e.g. to compute a second derivative.
+///
+/// The derivative of the first bucket is `None`. A gap, i.e. a bucket where the metric has no
+/// value, e.g. an empty bucket added with `min_doc_count: 0`, yields `None` for the gap and for
+/// the bucket following it.
+///
+/// # JSON Format
+/// ```json
+/// {
+///     "sales_per_day": {
+///         "date_histogram": { "field": "date", "fixed_interval": "1d" },
+///         "aggs": {
+///             "sales": { "sum": { "field": "price" } },
+///             "sales_deriv": { "derivative": { "buckets_path": "sales" } }
+///         }
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DerivativeAggregation {
+    /// The path to the metric to derive.
+    pub buckets_path: String,
+}
+
+impl DerivativeAggregation {
+    /// Creates a new [`DerivativeAggregation`] instance from a buckets path.
+    pub fn from_buckets_path(buckets_path: String) -> Self {
+        Self { buckets_path }
+    }
+    /// Returns the path to the metric to derive.
+    pub fn buckets_path(&self) -> &str {
+        &self.buckets_path
+    }
+
+    /// Computes the derivative of the ordered bucket `values`.
+    pub(crate) fn compute(&self, values: &[Option<f64>]) -> Vec<Option<f64>> {
+        let mut previous_value = None;
+        values
+            .iter()
+            .map(|&value| {
+                let derivative = match (previous_value, value) {
+                    (Some(previous_value), Some(value)) => Some(value - previous_value),
+                    _ => None,
+                };
+                previous_value = value;
+                derivative
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::aggregation::agg_req::Aggregations;
+    use crate::aggregation::tests::{exec_request_with_query, get_test_index_from_values};
+    use crate::TantivyError;
+
+    #[test]
+    fn test_derivative_agg() -> crate::Result<()> {
+        let index = get_test_index_from_values(false, &[1.0, 2.0, 3.0, 10.0, 11.0, 25.0])?;
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "histogram": {
+                "histogram": { "field": "score", "interval": 10.0 },
+                "aggs": {
+                    "sum_score": { "sum": { "field": "score" } },
+                    "sum_deriv": { "derivative": { "buckets_path": "sum_score" } },
+                    "sum_second_deriv": { "derivative": { "buckets_path": "sum_deriv" } },
+                    "count_deriv": { "derivative": { "buckets_path": "_count" } }
+                }
+            }
+        }))
+        .unwrap();
+
+        let res = exec_request_with_query(agg_req, &index, None)?;
+        let buckets = &res["histogram"]["buckets"];
+        assert_eq!(buckets[0]["sum_score"]["value"], 6.0);
+        assert_eq!(buckets[0]["sum_deriv"]["value"], serde_json::Value::Null);
+        assert_eq!(buckets[0]["count_deriv"]["value"], serde_json::Value::Null);
+        assert_eq!(buckets[1]["sum_score"]["value"], 21.0);
+        assert_eq!(buckets[1]["sum_deriv"]["value"], 15.0);
+        assert_eq!(
+            buckets[1]["sum_second_deriv"]["value"],
+            serde_json::Value::Null
+        );
+        assert_eq!(buckets[1]["count_deriv"]["value"], -1.0);
+        assert_eq!(buckets[2]["sum_score"]["value"], 25.0);
+        assert_eq!(buckets[2]["sum_deriv"]["value"], 4.0);
+        assert_eq!(buckets[2]["sum_second_deriv"]["value"], -11.0);
+        assert_eq!(buckets[2]["count_deriv"]["value"], -1.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_derivative_agg_gaps() -> crate::Result<()> {
+        let index = get_test_index_from_values(true, &[1.0, 2.0, 25.0, 31.0])?;
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "histogram": {
+                "histogram": { "field": "score", "interval": 10.0 },
+                "aggs": {
+                    "avg_score": { "avg": { "field": "score" } },
+                    "avg_deriv": { "derivative": { "buckets_path": "avg_score" } }
+                }
+            }
+        }))
+        .unwrap();
+
+        let res = exec_request_with_query(agg_req, &index, None)?;
+        let buckets = &res["histogram"]["buckets"];
+        assert_eq!(buckets[1]["doc_count"], 0);
+        assert_eq!(buckets[0]["avg_deriv"]["value"], serde_json::Value::Null);
+        assert_eq!(buckets[1]["avg_deriv"]["value"], serde_json::Value::Null);
+        assert_eq!(buckets[2]["avg_deriv"]["value"], serde_json::Value::Null);
+        assert_eq!(buckets[3]["avg_deriv"]["value"], 6.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_derivative_agg_invalid_requests() -> crate::Result<()> {
+        let index = get_test_index_from_values(false, &[1.0, 2.0])?;
+
+        let assert_invalid = |agg_req: serde_json::Value| {
+            let agg_req: Aggregations = serde_json::from_value(agg_req).unwrap();
+            let res = exec_request_with_query(agg_req, &index, None);
+            assert!(matches!(res, Err(TantivyError::AggregationError(_))));
+        };
+
+        // Not below a histogram
+        assert_invalid(json!({
+            "avg_score": { "avg": { "field": "score" } },
+            "avg_deriv": { "derivative": { "buckets_path": "avg_score" } }
+        }));
+        // Missing sibling
+        assert_invalid(json!({
+            "histogram": {
+                "histogram": { "field": "score", "interval": 10.0 },
+                "aggs": {
+                    "avg_deriv": { "derivative": { "buckets_path": "avg_score" } }
+                }
+            }
+        }));
+        // Cycle
+        assert_invalid(json!({
+            "histogram": {
+                "histogram": { "field": "score", "interval": 10.0 },
+                "aggs": {
+                    "avg_deriv": { "derivative": { "buckets_path": "avg_deriv" } }
+                }
+            }
+        }));
+
+        // Not below a histogram, on an index without segments
+        let index = get_test_index_from_values(false, &[])?;
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "avg_deriv": { "derivative": { "buckets_path": "_count" } }
+        }))
+        .unwrap();
+        let res = exec_request_with_query(agg_req, &index, None);
+        assert!(matches!(res, Err(TantivyError::AggregationError(_))));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,137 @@
+//! Module for all pipeline aggregations.
+//!
+//! Pipeline aggregations don't extract values from the documents. Instead they compute their
+//! value from the output of other aggregations, once the bucket aggregations have been
+//! finalized. They are added as sub-aggregations of the bucket aggregation whose buckets they
+//! read, and reference a sibling aggregation with a `buckets_path`.
+//!
+//! Since they only run on the final result, pipeline aggregations have no segment collector
+//! and no intermediate result.
+//!
+//! ## Supported Pipeline Aggregations
+//! - [Derivative](DerivativeAggregation)
+
+mod derivative;
+
+pub use derivative::*;
+
+use super::agg_req::{AggregationVariants, Aggregations};
+use super::agg_result::{AggregationResult, BucketEntry, MetricResult};
+use super::bucket::get_agg_name_and_property;
+use super::AggregationError;
+use crate::TantivyError;
+
+/// The `buckets_path` referencing the document count of a bucket.
+const DOC_COUNT_PATH: &str = "_count";
+
+/// Checks that pipeline aggregations are placed below a histogram and that their
+/// `buckets_path` references an existing sibling metric aggregation.
+pub(crate) fn validate_pipeline_aggs(
+    aggs: &Aggregations,
+    parent: Option<&AggregationVariants>,
+) -> crate::Result<()> {
+    let parent_is_histogram = matches!(
+        parent,
+        Some(AggregationVariants::Histogram(_) | AggregationVariants::DateHistogram(_))
+    );
+    for (name, agg) in aggs.iter() {
+        if let Some(derivative) = agg.agg.as_derivative() {
+            if !parent_is_histogram {
+                return Err(invalid_request(format!(
+                    "Derivative aggregation {name:?} must be a sub-aggregation of a histogram or \
+                     date_histogram aggregation"
+                )));
+            }
+            let (agg_name, _) = get_agg_name_and_property(derivative.buckets_path());
+            if agg_name != DOC_COUNT_PATH {
+                let target = aggs.get(agg_name).ok_or_else(|| {
+                    invalid_request(format!(
+                        "buckets_path {:?} of aggregation {name:?} does not reference a sibling \
+                         aggregation",
+                        derivative.buckets_path()
+                    ))
+                })?;
+                if matches!(
+                    target.agg,
+                    AggregationVariants::Range(_)
+                        | AggregationVariants::Histogram(_)
+                        | AggregationVariants::DateHistogram(_)
+                        | AggregationVariants::Terms(_)
+                ) {
+                    return Err(invalid_request(format!(
+                        "buckets_path {:?} of aggregation {name:?} must reference a metric \
+                         aggregation",
+                        derivative.buckets_path()
+                    )));
+                }
+            }
+        }
+        validate_pipeline_aggs(&agg.sub_aggregation, Some(&agg.agg))?;
+    }
+    // Detects cycles between pipeline aggregations.
+    pipeline_aggs_in_order(aggs)?;
+    Ok(())
+}
+
+/// Computes the pipeline aggregations of `sub_aggregation` on the final, ordered `buckets` of a
+/// histogram, and adds their results to the buckets.
+pub(crate) fn compute_pipeline_aggs(
+    buckets: &mut [BucketEntry],
+    sub_aggregation: &Aggregations,
+) -> crate::Result<()> {
+    for (name, derivative) in pipeline_aggs_in_order(sub_aggregation)? {
+        let values = buckets
+            .iter()
+            .map(|bucket| get_bucket_value(bucket, derivative.buckets_path()))
+            .collect::<crate::Result<Vec<_>>>()?;
+        for (bucket, value) in buckets.iter_mut().zip(derivative.compute(&values)) {
+            bucket.sub_aggregation.0.insert(
+                name.to_string(),
+                AggregationResult::MetricResult(MetricResult::Derivative(value.into())),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Returns the pipeline aggregations, ordered so that a pipeline aggregation comes after the
+/// pipeline aggregation it references, e.g. for a second derivative.
+fn pipeline_aggs_in_order(
+    aggs: &Aggregations,
+) -> crate::Result<Vec<(&str, &DerivativeAggregation)>> {
+    let mut pending: Vec<(&str, &DerivativeAggregation)> = aggs
+        .iter()
+        .filter_map(|(name, agg)| Some((name.as_str(), agg.agg.as_derivative()?)))
+        .collect();
+    let mut ordered = Vec::with_capacity(pending.len());
+    while !pending.is_empty() {
+        let (ready, blocked): (Vec<_>, Vec<_>) =
+            pending.iter().copied().partition(|(_, derivative)| {
+                let (agg_name, _) = get_agg_name_and_property(derivative.buckets_path());
+                !pending.iter().any(|(name, _)| *name == agg_name)
+            });
+        if ready.is_empty() {
+            return Err(invalid_request(format!(
+                "Pipeline aggregations {:?} reference each other in a cycle",
+                blocked.iter().map(|(name, _)| name).collect::<Vec<_>>()
+            )));
+        }
+        ordered.extend(ready);
+        pending = blocked;
+    }
+    Ok(ordered)
+}
+
+fn get_bucket_value(bucket: &BucketEntry, buckets_path: &str) -> crate::Result<Option<f64>> {
+    let (agg_name, agg_property) = get_agg_name_and_property(buckets_path);
+    if agg_name == DOC_COUNT_PATH {
+        return Ok(Some(bucket.doc_count as f64));
+    }
+    bucket
+        .sub_aggregation
+        .get_value_from_aggregation(agg_name, agg_property)
+}
+
+fn invalid_request(message: String) -> TantivyError {
+    TantivyError::AggregationError(AggregationError::InvalidRequest(message))
+}
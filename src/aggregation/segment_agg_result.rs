@@ -12,7 +12,8 @@ use super::bucket::{SegmentHistogramCollector, SegmentRangeCollector, SegmentTer
 use super::intermediate_agg_result::IntermediateAggregationResults;
 use super::metric::{
     AverageAggregation, CountAggregation, MaxAggregation, MinAggregation,
-    SegmentPercentilesCollector, SegmentStatsCollector, SegmentStatsType, StatsAggregation,
+    SegmentExtendedStatsCollector, SegmentPercentilesCollector, SegmentStatsCollector,
+    SegmentStatsType, SegmentTopMetricsCollector, SegmentWeightedAvgCollector, StatsAggregation,
     SumAggregation,
 };
 use crate::aggregation::bucket::TermMissingAgg;
@@ -129,24 +130,53 @@ pub(crate) fn build_single_agg_segment_collector(
             accessor_idx,
             *missing,
         ))),
-        Max(MaxAggregation { missing, .. }) => Ok(Box::new(SegmentStatsCollector::from_req(
-            req.field_type,
-            SegmentStatsType::Max,
-            accessor_idx,
-            *missing,
-        ))),
-        Min(MinAggregation { missing, .. }) => Ok(Box::new(SegmentStatsCollector::from_req(
-            req.field_type,
-            SegmentStatsType::Min,
-            accessor_idx,
-            *missing,
-        ))),
+        Max(MaxAggregation {
+            missing,
+            include_doc_address,
+            ..
+        }) => {
+            let collector = SegmentStatsCollector::from_req(
+                req.field_type,
+                SegmentStatsType::Max,
+                accessor_idx,
+                *missing,
+            );
+            if *include_doc_address {
+                Ok(Box::new(collector.with_extremum_doc(req.segment_ordinal)))
+            } else {
+                Ok(Box::new(collector))
+            }
+        }
+        Min(MinAggregation {
+            missing,
+            include_doc_address,
+            ..
+        }) => {
+            let collector = SegmentStatsCollector::from_req(
+                req.field_type,
+                SegmentStatsType::Min,
+                accessor_idx,
+                *missing,
+            );
+            if *include_doc_address {
+                Ok(Box::new(collector.with_extremum_doc(req.segment_ordinal)))
+            } else {
+                Ok(Box::new(collector))
+            }
+        }
         Stats(StatsAggregation { missing, .. }) => Ok(Box::new(SegmentStatsCollector::from_req(
             req.field_type,
             SegmentStatsType::Stats,
             accessor_idx,
             *missing,
         ))),
+        ExtendedStats(extended_stats_req) => Ok(Box::new(
+            SegmentExtendedStatsCollector::from_req_and_validate(
+                extended_stats_req,
+                req.field_type,
+                accessor_idx,
+            )?,
+        )),
         Sum(SumAggregation { missing, .. }) => Ok(Box::new(SegmentStatsCollector::from_req(
             req.field_type,
             SegmentStatsType::Sum,
@@ -160,6 +190,34 @@ pub(crate) fn build_single_agg_segment_collector(
                 accessor_idx,
             )?,
         )),
+        WeightedAvg(_) => {
+            let weight_field_type = req
+                .weight_accessor
+                .as_ref()
+                .map(|(_, column_type)| *column_type)
+                .ok_or_else(|| {
+                    crate::TantivyError::InternalError(
+                        "missing weight accessor for weighted_avg aggregation".to_string(),
+                    )
+                })?;
+            Ok(Box::new(SegmentWeightedAvgCollector::from_req(
+                req.field_type,
+                weight_field_type,
+                accessor_idx,
+            )))
+        }
+        TopMetrics(top_metrics_req) => {
+            Ok(Box::new(SegmentTopMetricsCollector::from_req_and_validate(
+                top_metrics_req,
+                req.field_type,
+                req.segment_ordinal,
+                accessor_idx,
+            )?))
+        }
+        Derivative(_) => Err(crate::TantivyError::InternalError(
+            "pipeline aggregations are computed on the final result and have no segment collector"
+                .to_string(),
+        )),
     }
 }
 
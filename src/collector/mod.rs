@@ -94,7 +94,7 @@ pub use histogram_collector::HistogramCollector;
 mod multi_collector;
 pub use self::multi_collector::{FruitHandle, MultiCollector, MultiFruit};
 
-mod top_collector;
+pub(crate) mod top_collector;
 
 mod top_score_collector;
 pub use self::top_score_collector::TopDocs;
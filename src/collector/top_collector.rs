@@ -16,6 +16,7 @@ use crate::{DocAddress, DocId, SegmentOrdinal, SegmentReader};
 /// Two elements are equal if their feature is equal, and regardless of whether `doc`
 /// is equal. This should be perfectly fine for this usage, but let's make sure this
 /// struct is never public.
+#[derive(Clone, Debug)]
 pub(crate) struct ComparableDoc<T, D> {
     pub feature: T,
     pub doc: D,
@@ -139,6 +140,7 @@ where T: PartialOrd + Clone
 /// The implementation is based on a `BinaryHeap`.
 /// The theoretical complexity for collecting the top `K` out of `n` documents
 /// is `O(n log K)`.
+#[derive(Clone, Debug)]
 pub(crate) struct TopSegmentCollector<T> {
     limit: usize,
     heap: BinaryHeap<ComparableDoc<T, DocId>>,
@@ -146,7 +148,7 @@ pub(crate) struct TopSegmentCollector<T> {
 }
 
 impl<T: PartialOrd> TopSegmentCollector<T> {
-    fn new(segment_ord: SegmentOrdinal, limit: usize) -> TopSegmentCollector<T> {
+    pub(crate) fn new(segment_ord: SegmentOrdinal, limit: usize) -> TopSegmentCollector<T> {
         TopSegmentCollector {
             limit,
             heap: BinaryHeap::with_capacity(limit),
@@ -60,7 +60,9 @@ impl InvertedIndexReader {
     }
 
     /// Returns the term info associated with the term.
-    pub fn get_term_info(&self, term: &Term) -> io::Result<Option<TermInfo>> {
+    ///
+    /// The term can be borrowed, e.g. a `Term<&[u8]>` pointing to an arena.
+    pub fn get_term_info<B: AsRef<[u8]>>(&self, term: &Term<B>) -> io::Result<Option<TermInfo>> {
         self.termdict.get(term.serialized_value_bytes())
     }
 
@@ -171,9 +173,9 @@ impl InvertedIndexReader {
     /// For instance, requesting [`IndexRecordOption::WithFreqs`] for a
     /// [`TextOptions`](crate::schema::TextOptions) that does not index position
     /// will return a [`SegmentPostings`] with `DocId`s and frequencies.
-    pub fn read_postings(
+    pub fn read_postings<B: AsRef<[u8]>>(
         &self,
-        term: &Term,
+        term: &Term<B>,
         option: IndexRecordOption,
     ) -> io::Result<Option<SegmentPostings>> {
         self.get_term_info(term)?
@@ -192,7 +194,7 @@ impl InvertedIndexReader {
     }
 
     /// Returns the number of documents containing the term.
-    pub fn doc_freq(&self, term: &Term) -> io::Result<u32> {
+    pub fn doc_freq<B: AsRef<[u8]>>(&self, term: &Term<B>) -> io::Result<u32> {
         Ok(self
             .get_term_info(term)?
             .map(|term_info| term_info.doc_freq)
@@ -5,7 +5,7 @@ use rustc_hash::FxHashMap;
 
 use crate::fastfield::FastValue;
 use crate::postings::{IndexingContext, IndexingPosition, PostingsWriter};
-use crate::schema::term::{JSON_PATH_SEGMENT_SEP, JSON_PATH_SEGMENT_SEP_STR};
+use crate::schema::term::{normalize_zero, JSON_PATH_SEGMENT_SEP, JSON_PATH_SEGMENT_SEP_STR};
 use crate::schema::{Field, Type, DATE_TIME_PRECISION_INDEXED};
 use crate::time::format_description::well_known::Rfc3339;
 use crate::time::{OffsetDateTime, UtcOffset};
@@ -185,12 +185,12 @@ fn index_json_value(
     }
 }
 
-enum TextOrDateTime<'a> {
+pub(crate) enum TextOrDateTime<'a> {
     Text(&'a str),
     DateTime(OffsetDateTime),
 }
 
-fn infer_type_from_str(text: &str) -> TextOrDateTime {
+pub(crate) fn infer_type_from_str(text: &str) -> TextOrDateTime {
     match OffsetDateTime::parse(text, &Rfc3339) {
         Ok(dt) => {
             let dt_utc = dt.to_offset(UtcOffset::UTC);
@@ -397,6 +397,9 @@ impl<'a> JsonTermWriter<'a> {
             DateTime::from_u64(val.to_u64())
                 .truncate(DATE_TIME_PRECISION_INDEXED)
                 .to_u64()
+        } else if T::to_type() == Type::F64 {
+            // `-0.0` and `0.0` map to the same term, as for non-JSON fields.
+            normalize_zero(f64::from_u64(val.to_u64())).to_u64()
         } else {
             val.to_u64()
         };
@@ -506,6 +509,19 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_f64_term_negative_zero() {
+        let field = Field::from_field_id(1);
+        let mut term = Term::with_type_and_field(Type::Json, field);
+        let mut json_writer = JsonTermWriter::wrap(&mut term, false);
+        json_writer.push_path_segment("color");
+        json_writer.set_fast_value(-0.0f64);
+        assert_eq!(
+            json_writer.term().serialized_term(),
+            b"\x00\x00\x00\x01jcolor\x00f\x80\x00\x00\x00\x00\x00\x00\x00"
+        )
+    }
+
     #[test]
     fn test_bool_term() {
         let field = Field::from_field_id(1);
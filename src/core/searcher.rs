@@ -124,7 +124,7 @@ impl Searcher {
 
     /// Return the overall number of documents containing
     /// the given term.
-    pub fn doc_freq(&self, term: &Term) -> crate::Result<u64> {
+    pub fn doc_freq<B: AsRef<[u8]>>(&self, term: &Term<B>) -> crate::Result<u64> {
         let mut total_doc_freq = 0;
         for segment_reader in &self.inner.segment_readers {
             let inverted_index = segment_reader.inverted_index(term.field())?;
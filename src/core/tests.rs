@@ -344,3 +344,42 @@ fn test_merging_segment_update_docfreq() {
     let term_info = inv_index.get_term_info(&term).unwrap().unwrap();
     assert_eq!(term_info.doc_freq, 12);
 }
+
+#[test]
+fn test_borrowed_terms() -> crate::Result<()> {
+    let mut schema_builder = Schema::builder();
+    let text_field = schema_builder.add_text_field("text", STRING);
+    let schema = schema_builder.build();
+    let index = Index::create_in_ram(schema);
+    let mut writer = index.writer_for_tests()?;
+    writer.add_document(doc!(text_field=>"a"))?;
+    writer.add_document(doc!(text_field=>"a"))?;
+    writer.add_document(doc!(text_field=>"b"))?;
+    writer.commit()?;
+    let searcher = index.reader()?.searcher();
+
+    // All of the terms of the query live in a single arena.
+    let mut arena: Vec<u8> = Vec::new();
+    let mut term_ranges = Vec::new();
+    for text in ["a", "b", "c"] {
+        let start = arena.len();
+        arena.extend_from_slice(Term::from_field_text(text_field, text).serialized_term());
+        term_ranges.push(start..arena.len());
+    }
+    let terms: Vec<Term<&[u8]>> = term_ranges
+        .into_iter()
+        .map(|range| Term::wrap(&arena[range]))
+        .collect();
+    let doc_freqs: Vec<u64> = terms
+        .iter()
+        .map(|term| searcher.doc_freq(term))
+        .collect::<crate::Result<_>>()?;
+    assert_eq!(doc_freqs, vec![2, 1, 0]);
+    let inverted_index = searcher.segment_reader(0).inverted_index(text_field)?;
+    let postings = inverted_index.read_postings(&terms[0], IndexRecordOption::Basic)?;
+    assert_eq!(postings.unwrap().doc_freq(), 2);
+    assert!(inverted_index
+        .read_postings(&terms[2], IndexRecordOption::Basic)?
+        .is_none());
+    Ok(())
+}
@@ -145,6 +145,26 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_term_query_empty_str() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", STRING);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text_field=>""))?;
+        index_writer.add_document(doc!(text_field=>"a"))?;
+        index_writer.add_document(doc!())?;
+        index_writer.commit()?;
+        let empty_term = Term::from_field_text(text_field, "");
+        let term_query = TermQuery::new(empty_term, IndexRecordOption::Basic);
+        let searcher = index.reader()?.searcher();
+        let top_docs = searcher.search(&term_query, &TopDocs::with_limit(10))?;
+        assert_eq!(top_docs.len(), 1);
+        assert_eq!(top_docs[0].1, DocAddress::new(0, 0));
+        Ok(())
+    }
+
     #[test]
     fn test_term_query_simple_seek() -> crate::Result<()> {
         let mut schema_builder = Schema::builder();
@@ -151,7 +151,7 @@ pub use self::named_field_document::NamedFieldDocument;
 pub use self::numeric_options::IntOptions;
 pub use self::numeric_options::NumericOptions;
 pub use self::schema::{DocParsingError, Schema, SchemaBuilder};
-pub use self::term::{Term, ValueBytes, JSON_END_OF_PATH};
+pub use self::term::{Collator, Term, ValueBytes, JSON_END_OF_PATH};
 pub use self::text_options::{TextFieldIndexing, TextOptions, STRING, TEXT};
 pub use self::value::Value;
 
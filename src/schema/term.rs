@@ -1,15 +1,25 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
 use std::convert::TryInto;
 use std::hash::{Hash, Hasher};
 use std::net::Ipv6Addr;
+use std::ops::Bound;
 use std::{fmt, str};
 
-use columnar::MonotonicallyMappableToU128;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use columnar::{ColumnType, MonotonicallyMappableToU128, MonotonicallyMappableToU64};
+use common::VInt;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
 
 use super::date_time_options::DATE_TIME_PRECISION_INDEXED;
 use super::Field;
+use crate::core::json_utils::{infer_type_from_str, JsonTermWriter, TextOrDateTime};
 use crate::fastfield::FastValue;
-use crate::schema::{Facet, Type};
-use crate::DateTime;
+use crate::schema::{Facet, FieldType, Schema, Type, Value};
+use crate::tokenizer::{TextAnalyzer, TokenStream};
+use crate::{DateTime, TantivyError};
 
 /// Separates the different segments of a json path.
 pub const JSON_PATH_SEGMENT_SEP: u8 = 1u8;
@@ -20,6 +30,17 @@ pub const JSON_PATH_SEGMENT_SEP_STR: &str =
 /// a JSON term binary representation.
 pub const JSON_END_OF_PATH: u8 = 0u8;
 
+/// A `Collator` defines a locale-aware order on texts.
+///
+/// It maps a text to a binary sort key, such that the byte order of the sort keys matches
+/// the linguistic order of the texts (e.g. ICU sort keys).
+pub trait Collator {
+    /// Returns the sort key of `text`.
+    ///
+    /// The sort key must not contain any `0u8` byte.
+    fn sort_key(&self, text: &str) -> Vec<u8>;
+}
+
 /// Term represents the value that the token can take.
 /// It's a serialized representation over different types.
 ///
@@ -31,6 +52,9 @@ pub const JSON_END_OF_PATH: u8 = 0u8;
 pub struct Term<B = Vec<u8>>(B)
 where B: AsRef<[u8]>;
 
+/// The alphabet used to encode geohashes.
+const GEOHASH_BASE32: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
 /// The number of bytes used as metadata by `Term`.
 const TERM_METADATA_LENGTH: usize = 5;
 
@@ -71,11 +95,6 @@ impl Term {
         self.0[4] = typ.to_code();
     }
 
-    /// Is empty if there are no value bytes.
-    pub fn is_empty(&self) -> bool {
-        self.0.len() == TERM_METADATA_LENGTH
-    }
-
     /// Builds a term given a field, and a `Ipv6Addr`-value
     pub fn from_field_ip_addr(field: Field, ip_addr: Ipv6Addr) -> Term {
         let mut term = Self::with_type_and_field(Type::IpAddr, field);
@@ -89,13 +108,29 @@ impl Term {
     }
 
     /// Builds a term given a field, and a `i64`-value
+    ///
+    /// The value is mapped to a `u64` preserving the order, so that a smaller `i64` yields a
+    /// smaller term. It can be read back using `term.value().as_i64()`.
     pub fn from_field_i64(field: Field, val: i64) -> Term {
         Term::from_fast_value(field, &val)
     }
 
     /// Builds a term given a field, and a `f64`-value
+    ///
+    /// `-0.0` is normalized to `0.0`, so that both zeros map to the same term.
+    ///
+    /// Segments indexed before this normalization may contain `-0.0` terms. These are not
+    /// matched by exact queries anymore, nor by range queries whose bound is `-0.0`, as the
+    /// bound is normalized to `0.0` too. Reindex the affected documents to make them reachable.
     pub fn from_field_f64(field: Field, val: f64) -> Term {
-        Term::from_fast_value(field, &val)
+        Term::from_fast_value(field, &normalize_zero(val))
+    }
+
+    /// Builds a term given a field, and a set of packed boolean flags.
+    ///
+    /// The flags are stored as a `u64`-value, the bit `i` holding the flag `i`.
+    pub fn from_field_flags(field: Field, flags: u64) -> Term {
+        Term::from_field_u64(field, flags)
     }
 
     /// Builds a term given a field, and a `bool`-value
@@ -108,6 +143,65 @@ impl Term {
         Term::from_fast_value(field, &val.truncate(DATE_TIME_PRECISION_INDEXED))
     }
 
+    /// Builds a term given a field, its type as defined in the schema, and a value.
+    ///
+    /// The value is encoded according to the field type, and text values are used as is,
+    /// without being tokenized.
+    ///
+    /// Returns an error if the value does not match the field type, or if the field is
+    /// a JSON field, as JSON terms also require a path.
+    pub fn from_field_value(
+        field: Field,
+        field_type: &FieldType,
+        value: &Value,
+    ) -> crate::Result<Term> {
+        let term = match (field_type, value) {
+            (FieldType::Str(_), Value::Str(text)) => Term::from_field_text(field, text),
+            (FieldType::U64(_), Value::U64(val)) => Term::from_field_u64(field, *val),
+            (FieldType::I64(_), Value::I64(val)) => Term::from_field_i64(field, *val),
+            (FieldType::F64(_), Value::F64(val)) => Term::from_field_f64(field, *val),
+            (FieldType::Bool(_), Value::Bool(val)) => Term::from_field_bool(field, *val),
+            (FieldType::Date(_), Value::Date(date)) => Term::from_field_date(field, *date),
+            (FieldType::Facet(_), Value::Facet(facet)) => Term::from_facet(field, facet),
+            (FieldType::Bytes(_), Value::Bytes(bytes)) => Term::from_field_bytes(field, bytes),
+            (FieldType::IpAddr(_), Value::IpAddr(ip_addr)) => {
+                Term::from_field_ip_addr(field, *ip_addr)
+            }
+            _ => {
+                return Err(TantivyError::InvalidArgument(format!(
+                    "Cannot build a term of type {:?} from the value {value:?}",
+                    field_type.value_type()
+                )));
+            }
+        };
+        Ok(term)
+    }
+
+    /// Builds a term given a field, and a raw `u64` value read from a fast field column
+    /// of type `column_type`, e.g. through a `Column<u64>`.
+    ///
+    /// Columns and terms share the same monotonic `u64` mapping, so this is equivalent to
+    /// building the term from the decoded value. Dates are truncated to the indexed precision,
+    /// as in [`Term::from_field_date`].
+    ///
+    /// Returns `None` if the column values are not `u64` mapped, i.e. for `Str`, `Bytes`
+    /// and `IpAddr` columns.
+    pub fn from_field_column_value(
+        field: Field,
+        column_type: ColumnType,
+        raw_value: u64,
+    ) -> Option<Term> {
+        let term = match column_type {
+            ColumnType::U64 => Term::from_field_u64(field, raw_value),
+            ColumnType::I64 => Term::from_field_i64(field, i64::from_u64(raw_value)),
+            ColumnType::F64 => Term::from_field_f64(field, f64::from_u64(raw_value)),
+            ColumnType::Bool => Term::from_field_bool(field, bool::from_u64(raw_value)),
+            ColumnType::DateTime => Term::from_field_date(field, DateTime::from_u64(raw_value)),
+            ColumnType::Str | ColumnType::Bytes | ColumnType::IpAddr => return None,
+        };
+        Some(term)
+    }
+
     /// Creates a `Term` given a facet.
     pub fn from_facet(field: Field, facet: &Facet) -> Term {
         let facet_encoded_str = facet.encoded_str();
@@ -115,21 +209,340 @@ impl Term {
     }
 
     /// Builds a term given a field, and a string value
+    ///
+    /// An empty string yields a term with no value bytes, made of the field and type only.
+    /// Such a term matches documents that contain an explicit empty string for the field,
+    /// and not documents that do not have the field at all.
     pub fn from_field_text(field: Field, text: &str) -> Term {
         Term::with_bytes_and_field_and_payload(Type::Str, field, text.as_bytes())
     }
 
+    /// Builds a term given a JSON field, a json path and a JSON value.
+    ///
+    /// The term is encoded the same way the indexer encodes JSON values, i.e.
+    /// `[field][JSON_TYPE][JSON_PATH][JSON_END_OF_PATH][VALUE_TYPE][VALUE_BYTES]`.
+    /// Segments of `json_path` are separated by `.`, which can be escaped as `\.`.
+    ///
+    /// Numbers are encoded as `i64` if possible, then `u64`, then `f64`, normalizing `-0.0` to
+    /// `0.0` as in [`Term::from_field_f64`]. Strings that are
+    /// RFC3339 dates are encoded as dates, other strings are used as is, without being
+    /// tokenized.
+    ///
+    /// Returns `None` for `null`, arrays and objects, which are not indexed as a single term.
+    pub fn from_field_json_path(
+        field: Field,
+        json_path: &str,
+        value: &serde_json::Value,
+    ) -> Option<Term> {
+        let mut term = Term::with_capacity(json_path.len() + 16);
+        let mut json_term_writer =
+            JsonTermWriter::from_field_and_json_path(field, json_path, false, &mut term);
+        match value {
+            serde_json::Value::Bool(val) => json_term_writer.set_fast_value(*val),
+            serde_json::Value::Number(number) => {
+                if let Some(number_i64) = number.as_i64() {
+                    json_term_writer.set_fast_value(number_i64);
+                } else if let Some(number_u64) = number.as_u64() {
+                    json_term_writer.set_fast_value(number_u64);
+                } else if let Some(number_f64) = number.as_f64() {
+                    json_term_writer.set_fast_value(number_f64);
+                }
+            }
+            serde_json::Value::String(text) => match infer_type_from_str(text) {
+                TextOrDateTime::Text(text) => json_term_writer.set_str(text),
+                TextOrDateTime::DateTime(dt) => {
+                    json_term_writer.set_fast_value(DateTime::from_utc(dt))
+                }
+            },
+            serde_json::Value::Null
+            | serde_json::Value::Array(_)
+            | serde_json::Value::Object(_) => return None,
+        }
+        Some(term)
+    }
+
+    /// Builds a text term given a field, and a string value normalized by `normalizer`.
+    ///
+    /// `normalizer` should match the tokenizer used to index the field, e.g. a `RawTokenizer`
+    /// followed by a `LowerCaser`, so that the term matches the normalized terms of the term
+    /// dictionary. The text of all of the tokens emitted by `normalizer` is concatenated.
+    pub fn from_field_text_normalized(
+        field: Field,
+        text: &str,
+        normalizer: &mut TextAnalyzer,
+    ) -> Term {
+        Term::from_field_text(field, &normalize_text(text, normalizer))
+    }
+
+    /// Returns the range of text terms starting with `prefix`.
+    ///
+    /// The lower bound is inclusive and is the prefix itself. The upper bound is exclusive and
+    /// is computed on the utf-8 bytes of the prefix, by incrementing its last byte. Trailing
+    /// `0xFF` bytes are dropped first, carrying the increment into the preceding byte. This
+    /// cannot happen for valid utf-8, but the upper bound is still unbounded if no byte can be
+    /// incremented, e.g. if the prefix is empty.
+    ///
+    /// The upper bound may not be valid utf-8, which is fine as terms are compared bytewise.
+    pub fn prefix_range(field: Field, prefix: &str) -> (Bound<Term>, Bound<Term>) {
+        let lower_bound = Term::from_field_text(field, prefix);
+        let upper_bound = prefix_upper_bound(Type::Str, field, prefix.as_bytes());
+        (Bound::Included(lower_bound), upper_bound)
+    }
+
+    /// Returns the range of text terms starting with `prefix`, once normalized by `normalizer`.
+    ///
+    /// `normalizer` should match the tokenizer used to index the field, e.g. a `RawTokenizer`
+    /// followed by a `LowerCaser`, so that the bounds live in the same case-folded space as
+    /// the term dictionary. The bounds are computed on the normalized bytes, so foldings that
+    /// change the byte length (e.g. `ß` to `ss`) are handled correctly.
+    ///
+    /// See [`Term::prefix_range`] for the bounds.
+    pub fn text_prefix_range_normalized(
+        field: Field,
+        prefix: &str,
+        normalizer: &mut TextAnalyzer,
+    ) -> (Bound<Term>, Bound<Term>) {
+        let normalized_prefix = normalize_text(prefix, normalizer);
+        Term::prefix_range(field, &normalized_prefix)
+    }
+
+    /// Builds a term given a field, a text and a binary suffix.
+    ///
+    /// The value is encoded as `[text length (vint)][text][suffix]`, which makes it possible
+    /// to recover both parts using [`Term::text_part`] and [`Term::suffix`].
+    /// The resulting term is of type [`Type::Bytes`].
+    pub fn from_field_text_with_suffix(field: Field, text: &str, suffix: &[u8]) -> Term {
+        let mut term = Self::with_capacity(10 + text.len() + suffix.len());
+        term.set_field_and_type(field, Type::Bytes);
+        VInt(text.len() as u64).serialize_into_vec(&mut term.0);
+        term.0.extend_from_slice(text.as_bytes());
+        term.0.extend_from_slice(suffix);
+        term
+    }
+
+    /// Builds a term given a field, a language code and a text.
+    ///
+    /// The value is encoded as `[language code (2 bytes, big endian)][text]`, so that the
+    /// terms of a given language are contiguous in the term dictionary, and prefix or range
+    /// queries on a language do not overlap with other languages.
+    /// The resulting term is of type [`Type::Bytes`].
+    pub fn from_field_text_lang(field: Field, lang_code: u16, text: &str) -> Term {
+        let mut term = Self::with_capacity(2 + text.len());
+        term.set_field_and_type(field, Type::Bytes);
+        term.0.extend_from_slice(&lang_code.to_be_bytes());
+        term.0.extend_from_slice(text.as_bytes());
+        term
+    }
+
+    /// Builds a term given a field, and an interval `[start, end]`.
+    ///
+    /// The value is encoded as the order preserving `u64` mapping of `start` followed by the
+    /// one of `end`, both big endian. Terms are hence sorted by `start`, then by `end`.
+    ///
+    /// This makes it possible to find the intervals overlapping `[query_start, query_end]`
+    /// with a single range scan: the intervals with `start <= query_end` are exactly the
+    /// terms lower than or equal to `from_field_interval(field, query_end, i64::MAX)`, i.e. the
+    /// scan uses this term as an inclusive upper bound. Among them, the overlapping ones are
+    /// those with `end >= query_start`, checked using [`Term::interval`].
+    /// The resulting term is of type [`Type::Bytes`].
+    pub fn from_field_interval(field: Field, start: i64, end: i64) -> Term {
+        let mut term = Self::with_capacity(16);
+        term.set_field_and_type(field, Type::Bytes);
+        term.0.extend_from_slice(&start.to_u64().to_be_bytes());
+        term.0.extend_from_slice(&end.to_u64().to_be_bytes());
+        term
+    }
+
+    /// Builds a term given a field, and a `u64`-value using a compact encoding.
+    ///
+    /// The value is encoded as `[number of significant bytes][significant bytes, big endian]`,
+    /// so that small values take fewer bytes than the fixed 8-byte `u64` encoding.
+    /// Because the length comes first, the byte order still matches the numeric order.
+    /// The resulting term is of type [`Type::Bytes`].
+    pub fn from_field_varint(field: Field, val: u64) -> Term {
+        let num_bytes = 8 - val.leading_zeros() as usize / 8;
+        let mut term = Self::with_capacity(1 + num_bytes);
+        term.set_field_and_type(field, Type::Bytes);
+        term.0.push(num_bytes as u8);
+        term.0
+            .extend_from_slice(&val.to_be_bytes()[8 - num_bytes..]);
+        term
+    }
+
+    /// Builds a term given a field, and the geohash of a point computed with `precision`
+    /// characters.
+    ///
+    /// The geohash is stored as text, so that the points of a geohash cell are the terms
+    /// starting with the cell's geohash, and can be selected with a prefix query.
+    ///
+    /// # Panics
+    /// Panics if `lat` is not within `[-90, 90]` or `lon` is not within `[-180, 180]`.
+    pub fn from_field_geohash(field: Field, lat: f64, lon: f64, precision: usize) -> Term {
+        assert!(
+            (-90.0..=90.0).contains(&lat),
+            "Latitude must be within [-90, 90], got {lat}"
+        );
+        assert!(
+            (-180.0..=180.0).contains(&lon),
+            "Longitude must be within [-180, 180], got {lon}"
+        );
+        Term::from_field_text(field, &geohash(lat, lon, precision))
+    }
+
+    /// Builds a term given a field, and a text ordered according to `collator`.
+    ///
+    /// The value is encoded as `[sort key][0u8][text]`: terms are ordered by the sort key,
+    /// and the original text can be recovered using [`Term::collated_text`].
+    /// The resulting term is of type [`Type::Bytes`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sort key returned by `collator` contains a `0u8` byte.
+    pub fn from_field_text_collated(field: Field, text: &str, collator: &dyn Collator) -> Term {
+        let sort_key = collator.sort_key(text);
+        assert!(!sort_key.contains(&0u8), "Sort keys must not contain 0u8");
+        let mut term = Self::with_capacity(sort_key.len() + 1 + text.len());
+        term.set_field_and_type(field, Type::Bytes);
+        term.0.extend_from_slice(&sort_key);
+        term.0.push(0u8);
+        term.0.extend_from_slice(text.as_bytes());
+        term
+    }
+
     /// Builds a term bytes.
     pub fn from_field_bytes(field: Field, bytes: &[u8]) -> Term {
         Term::with_bytes_and_field_and_payload(Type::Bytes, field, bytes)
     }
 
+    /// Parses a term from its query string representation, as returned by
+    /// [`Term::to_query_string`].
+    ///
+    /// The field name is resolved using the schema, and the type has to match
+    /// the type of the field.
+    pub fn from_query_string(query_str: &str, schema: &Schema) -> crate::Result<Term> {
+        let (field, typ_and_value) = query_str
+            .match_indices('/')
+            .find_map(|(pos, _)| {
+                let field = schema.get_field(&query_str[..pos]).ok()?;
+                Some((field, &query_str[pos + 1..]))
+            })
+            .ok_or_else(|| {
+                let field_name = query_str.split('/').next().unwrap_or(query_str);
+                TantivyError::FieldNotFound(field_name.to_string())
+            })?;
+        let (typ_name, value_str) = typ_and_value.split_once(':').ok_or_else(|| {
+            TantivyError::InvalidArgument(format!(
+                "Missing type in term query string {query_str:?}"
+            ))
+        })?;
+        let typ = Type::iter_values()
+            .find(|typ| typ.name().eq_ignore_ascii_case(typ_name))
+            .ok_or_else(|| {
+                TantivyError::InvalidArgument(format!(
+                    "Unknown type {typ_name:?} in term query string {query_str:?}"
+                ))
+            })?;
+        let field_typ = schema.get_field_entry(field).field_type().value_type();
+        if field_typ != typ {
+            return Err(TantivyError::InvalidArgument(format!(
+                "Type {typ:?} does not match the type {field_typ:?} of the field in term query \
+                 string {query_str:?}"
+            )));
+        }
+        let invalid_value = || {
+            TantivyError::InvalidArgument(format!(
+                "Invalid {typ:?} value in term query string {query_str:?}"
+            ))
+        };
+        let term = match typ {
+            Type::Str => Term::from_field_text(field, value_str),
+            Type::U64 => {
+                Term::from_field_u64(field, value_str.parse().map_err(|_| invalid_value())?)
+            }
+            Type::I64 => {
+                Term::from_field_i64(field, value_str.parse().map_err(|_| invalid_value())?)
+            }
+            Type::F64 => {
+                Term::from_field_f64(field, value_str.parse().map_err(|_| invalid_value())?)
+            }
+            Type::Bool => {
+                Term::from_field_bool(field, value_str.parse().map_err(|_| invalid_value())?)
+            }
+            Type::Date => {
+                let date_time =
+                    OffsetDateTime::parse(value_str, &Rfc3339).map_err(|_| invalid_value())?;
+                Term::from_field_date(field, DateTime::from_utc(date_time))
+            }
+            Type::Facet => {
+                let facet = Facet::from_text(value_str).map_err(|_| invalid_value())?;
+                Term::from_facet(field, &facet)
+            }
+            Type::Bytes | Type::Json => {
+                let bytes = BASE64.decode(value_str).map_err(|_| invalid_value())?;
+                Term::with_bytes_and_field_and_payload(typ, field, &bytes)
+            }
+            Type::IpAddr => {
+                Term::from_field_ip_addr(field, value_str.parse().map_err(|_| invalid_value())?)
+            }
+        };
+        Ok(term)
+    }
+
+    /// Decodes the values of fast value terms (`u64`, `i64`, `f64`, `bool` or date)
+    /// serialized back to back in `packed_terms`, e.g. by concatenating their
+    /// [`Term::serialized_term`].
+    ///
+    /// All of the terms must share the same field and type. This is checked once upfront,
+    /// so that the values can then be decoded in bulk. The values are returned in their
+    /// `u64` mapped representation, in the same order as the terms.
+    ///
+    /// Returns `None` if `packed_terms` is not made of such terms.
+    pub fn decode_packed_fast_values(
+        packed_terms: &[u8],
+    ) -> Option<impl Iterator<Item = u64> + '_> {
+        const FAST_VALUE_TERM_LEN: usize = TERM_METADATA_LENGTH + 8;
+        if packed_terms.len() % FAST_VALUE_TERM_LEN != 0 {
+            return None;
+        }
+        let terms = packed_terms.chunks_exact(FAST_VALUE_TERM_LEN);
+        if let Some(metadata) = packed_terms.get(..TERM_METADATA_LENGTH) {
+            let typ = Type::from_code(metadata[4])?;
+            if !matches!(
+                typ,
+                Type::U64 | Type::I64 | Type::F64 | Type::Bool | Type::Date
+            ) {
+                return None;
+            }
+            if !terms
+                .clone()
+                .all(|term| &term[..TERM_METADATA_LENGTH] == metadata)
+            {
+                return None;
+            }
+        }
+        Some(terms.map(|term| u64::from_be_bytes(term[TERM_METADATA_LENGTH..].try_into().unwrap())))
+    }
+
     /// Removes the value_bytes and set the field and type code.
     pub(crate) fn clear_with_field_and_type(&mut self, typ: Type, field: Field) {
         self.truncate_value_bytes(0);
         self.set_field_and_type(field, typ);
     }
 
+    /// Removes the value_bytes, keeping the field and the type code.
+    ///
+    /// The underlying buffer is not deallocated, so that a single `Term` can be reused to build
+    /// many terms in a loop. Use [`Term::set_field`] and [`Term::clear_with_type`] to change the
+    /// field and the type of the cleared term.
+    pub fn clear(&mut self) {
+        self.truncate_value_bytes(0);
+    }
+
+    /// Sets the field of the term, keeping the type code and the value_bytes untouched.
+    pub fn set_field(&mut self, field: Field) {
+        self.0[0..4].clone_from_slice(field.field_id().to_be_bytes().as_ref());
+    }
+
     /// Removes the value_bytes and set the type code.
     pub fn clear_with_type(&mut self, typ: Type) {
         self.truncate_value_bytes(0);
@@ -157,8 +570,10 @@ impl Term {
     }
 
     /// Sets a `f64` value in the term.
+    ///
+    /// `-0.0` is normalized to `0.0`, so that both zeros map to the same term.
     pub fn set_f64(&mut self, val: f64) {
-        self.set_fast_value(val);
+        self.set_fast_value(normalize_zero(val));
     }
 
     /// Sets a `bool` value in the term.
@@ -166,6 +581,20 @@ impl Term {
         self.set_fast_value(val);
     }
 
+    /// Sets the flag `flag_id` of a term built with [`Term::from_field_flags`].
+    ///
+    /// # Panics
+    /// Panics if the term does not hold a `u64`-value, or if `flag_id >= 64`.
+    pub fn set_flag(&mut self, flag_id: u32, val: bool) {
+        assert!(flag_id < 64, "Flag id must be lower than 64, got {flag_id}");
+        let flags = self
+            .value()
+            .as_u64()
+            .expect("Flags can only be set on a u64 term");
+        let mask = 1u64 << flag_id;
+        self.set_u64(if val { flags | mask } else { flags & !mask });
+    }
+
     fn set_fast_value<T: FastValue>(&mut self, val: T) {
         self.set_bytes(val.to_u64().to_be_bytes().as_ref());
     }
@@ -191,11 +620,6 @@ impl Term {
         self.0.truncate(len + TERM_METADATA_LENGTH);
     }
 
-    /// The length of the bytes.
-    pub fn len_bytes(&self) -> usize {
-        self.0.len() - TERM_METADATA_LENGTH
-    }
-
     /// Appends value bytes to the Term.
     ///
     /// This function returns the segment that has just been added.
@@ -241,10 +665,268 @@ where B: AsRef<[u8]>
         self.value().typ()
     }
 
+    /// Is empty if there are no value bytes.
+    pub fn is_empty(&self) -> bool {
+        self.len_bytes() == 0
+    }
+
+    /// The length of the value bytes, excluding the field and the type.
+    ///
+    /// Is 0 for a malformed term shorter than the field and the type.
+    pub fn len_bytes(&self) -> usize {
+        self.0.as_ref().len().saturating_sub(TERM_METADATA_LENGTH)
+    }
+
+    /// The length of the serialized term, including the field and the type.
+    ///
+    /// See [`Term::len_bytes`] for the length of the value bytes only.
+    pub fn serialized_len(&self) -> usize {
+        self.0.as_ref().len()
+    }
+
     /// Returns the field.
     pub fn field(&self) -> Field {
-        let field_id_bytes: [u8; 4] = (&self.0.as_ref()[..4]).try_into().unwrap();
-        Field::from_field_id(u32::from_be_bytes(field_id_bytes))
+        Field::from_field_id(u32::from_be_bytes(*self.field_bytes()))
+    }
+
+    /// Returns the big endian encoded field id, i.e. the first 4 bytes of the term.
+    ///
+    /// This is useful to hash or route terms by field without decoding the [`Field`].
+    ///
+    /// # Panics
+    /// Panics if the term is shorter than 4 bytes, which can only happen for a term wrapping
+    /// invalid bytes.
+    pub fn field_bytes(&self) -> &[u8; 4] {
+        self.0.as_ref()[..4].try_into().unwrap()
+    }
+
+    /// Returns a copy of the term with a `tiebreak` appended to its value, encoded as a
+    /// 8-byte big endian suffix.
+    ///
+    /// Terms sharing the same value are then ordered by their tiebreak.
+    /// The order on the values is preserved for fixed width values (e.g. numbers or dates).
+    /// For variable length values such as text, a value that is a prefix of another value
+    /// may not sort before it anymore.
+    pub fn with_tiebreak_suffix(&self, tiebreak: u64) -> Term {
+        let term_bytes = self.0.as_ref();
+        let mut data = Vec::with_capacity(term_bytes.len() + 8);
+        data.extend_from_slice(term_bytes);
+        data.extend_from_slice(&tiebreak.to_be_bytes());
+        Term(data)
+    }
+
+    /// Returns the flag `flag_id` of a term built with [`Term::from_field_flags`].
+    ///
+    /// Returns `None` if the term is not of the u64 type, or if `flag_id >= 64`.
+    pub fn get_flag(&self, flag_id: u32) -> Option<bool> {
+        if flag_id >= 64 {
+            return None;
+        }
+        let flags = self.value().as_u64()?;
+        Some(flags & (1u64 << flag_id) != 0)
+    }
+
+    /// Returns a canonical string representation of the term, that can be parsed back
+    /// using [`Term::from_query_string`].
+    ///
+    /// The format is `<field name>/<type>:<value>`, e.g. `count/u64:983` or `title/str:hello`.
+    /// Dates are formatted as RFC 3339, facets as their path, ip addresses as IPv6 and
+    /// bytes and JSON values are base64 encoded.
+    ///
+    /// A malformed term with an invalid or missing type code is formatted as
+    /// `<field name>/?:<base64 encoded value>`, which [`Term::from_query_string`] rejects.
+    ///
+    /// # Panics
+    /// Panics if the field of the term does not belong to the schema, or if the term is
+    /// shorter than 4 bytes, which can only happen for a term wrapping invalid bytes.
+    pub fn to_query_string(&self, schema: &Schema) -> String {
+        let field_name = schema.get_field_name(self.field());
+        let value = self.value();
+        let typ = match value.try_typ() {
+            Some(typ) => typ,
+            None => return format!("{field_name}/?:{}", BASE64.encode(value.as_serialized())),
+        };
+        let value_str = match typ {
+            Type::Str => String::from_utf8_lossy(value.value_bytes()).into_owned(),
+            Type::U64 => opt_to_string(value.as_u64()),
+            Type::I64 => opt_to_string(value.as_i64()),
+            Type::F64 => opt_to_string(value.as_f64()),
+            Type::Bool => opt_to_string(value.as_bool()),
+            Type::Date => value
+                .as_date()
+                .and_then(|date| date.into_utc().format(&Rfc3339).ok())
+                .unwrap_or_default(),
+            Type::Facet => opt_to_string(value.as_facet()),
+            Type::Bytes | Type::Json => BASE64.encode(value.value_bytes()),
+            Type::IpAddr => opt_to_string(value.as_ip_addr()),
+        };
+        format!("{field_name}/{}:{value_str}", typ.name().to_lowercase())
+    }
+
+    /// Returns a human readable representation of the term for debugging purposes, in the
+    /// format `<field name>:<value>`.
+    ///
+    /// The value is decoded according to the type of the field in the schema: text is quoted,
+    /// numbers, booleans, dates, facets and ip addresses are printed as such, and bytes are
+    /// printed as hex. JSON values are printed as `<path>=<value>`.
+    ///
+    /// This never panics: if the field does not belong to the schema, its id is printed
+    /// instead of its name, and if the value can not be decoded as the field's type, the value
+    /// bytes are printed as hex. A malformed term that is too short to hold a field is printed
+    /// as `?:<hex of the term bytes>`.
+    pub fn to_readable_string(&self, schema: &Schema) -> String {
+        let field_id_bytes: [u8; 4] = match self.0.as_ref().get(..4) {
+            Some(field_id_bytes) => field_id_bytes.try_into().unwrap(),
+            None => return format!("?:{}", to_hex(self.0.as_ref())),
+        };
+        let field = Field::from_field_id(u32::from_be_bytes(field_id_bytes));
+        let value = self.value();
+        let value_hex = || {
+            to_hex(
+                self.0
+                    .as_ref()
+                    .get(TERM_METADATA_LENGTH..)
+                    .unwrap_or_default(),
+            )
+        };
+        match schema
+            .fields()
+            .find(|(schema_field, _)| *schema_field == field)
+        {
+            Some((_, field_entry)) => {
+                let value_str = if Some(field_entry.field_type().value_type()) == value.try_typ() {
+                    value.readable_value()
+                } else {
+                    None
+                };
+                format!(
+                    "{}:{}",
+                    field_entry.name(),
+                    value_str.unwrap_or_else(value_hex)
+                )
+            }
+            None => format!("{}:{}", field.field_id(), value_hex()),
+        }
+    }
+
+    /// Returns the text of a term built with [`Term::from_field_text_with_suffix`].
+    ///
+    /// Returns `None` if the term is not of the bytes type, or if its value is not a valid
+    /// length-delimited text.
+    pub fn text_part(&self) -> Option<&str> {
+        self.text_and_suffix().map(|(text, _)| text)
+    }
+
+    /// Returns the suffix of a term built with [`Term::from_field_text_with_suffix`].
+    ///
+    /// Returns `None` if the term is not of the bytes type, or if its value is not a valid
+    /// length-delimited text.
+    pub fn suffix(&self) -> Option<&[u8]> {
+        self.text_and_suffix().map(|(_, suffix)| suffix)
+    }
+
+    /// Returns the language code of a term built with [`Term::from_field_text_lang`].
+    ///
+    /// Returns `None` if the term is not of the bytes type, or if its value is too short.
+    pub fn lang_code(&self) -> Option<u16> {
+        self.lang_code_and_text().map(|(lang_code, _)| lang_code)
+    }
+
+    /// Returns the text of a term built with [`Term::from_field_text_lang`], without the
+    /// language code.
+    ///
+    /// Returns `None` if the term is not of the bytes type, if its value is too short, or if
+    /// the text is not valid utf-8.
+    pub fn lang_text(&self) -> Option<&str> {
+        self.lang_code_and_text().map(|(_, text)| text)
+    }
+
+    fn lang_code_and_text(&self) -> Option<(u16, &str)> {
+        let value_bytes = self.value_bytes_of_type(Type::Bytes)?;
+        if value_bytes.len() < 2 {
+            return None;
+        }
+        let (lang_code_bytes, text_bytes) = value_bytes.split_at(2);
+        let lang_code = u16::from_be_bytes(lang_code_bytes.try_into().unwrap());
+        Some((lang_code, str::from_utf8(text_bytes).ok()?))
+    }
+
+    /// Returns the `(start, end)` endpoints of a term built with
+    /// [`Term::from_field_interval`].
+    ///
+    /// Returns `None` if the term is not of the bytes type, or if its value is not 16 bytes
+    /// long.
+    pub fn interval(&self) -> Option<(i64, i64)> {
+        let value_bytes: [u8; 16] = self.value_bytes_of_type(Type::Bytes)?.try_into().ok()?;
+        let start = u64::from_be_bytes(value_bytes[..8].try_into().unwrap());
+        let end = u64::from_be_bytes(value_bytes[8..].try_into().unwrap());
+        Some((i64::from_u64(start), i64::from_u64(end)))
+    }
+
+    /// Returns the value of a term built with [`Term::from_field_varint`].
+    ///
+    /// Returns `None` if the term is not of the bytes type, or if its value is not a valid
+    /// compact `u64` encoding.
+    pub fn varint(&self) -> Option<u64> {
+        let (&num_bytes, val_bytes) = self.value_bytes_of_type(Type::Bytes)?.split_first()?;
+        if num_bytes as usize != val_bytes.len() || num_bytes > 8 {
+            return None;
+        }
+        let mut buffer = [0u8; 8];
+        buffer[8 - val_bytes.len()..].copy_from_slice(val_bytes);
+        Some(u64::from_be_bytes(buffer))
+    }
+
+    /// Returns the geohash of a term built with [`Term::from_field_geohash`].
+    ///
+    /// Returns `None` if the term is not of the string type, or if the bytes are not
+    /// valid utf-8.
+    pub fn geohash(&self) -> Option<&str> {
+        str::from_utf8(self.value_bytes_of_type(Type::Str)?).ok()
+    }
+
+    /// Returns the collation sort key of a term built with [`Term::from_field_text_collated`].
+    ///
+    /// Returns `None` if the term is not of the bytes type, or if its value does not contain
+    /// a sort key.
+    pub fn collation_key(&self) -> Option<&[u8]> {
+        self.collation_key_and_text().map(|(sort_key, _)| sort_key)
+    }
+
+    /// Returns the original text of a term built with [`Term::from_field_text_collated`].
+    ///
+    /// Returns `None` if the term is not of the bytes type, if its value does not contain
+    /// a sort key, or if the text is not valid utf-8.
+    pub fn collated_text(&self) -> Option<&str> {
+        self.collation_key_and_text().map(|(_, text)| text)
+    }
+
+    fn collation_key_and_text(&self) -> Option<(&[u8], &str)> {
+        let value_bytes = self.value_bytes_of_type(Type::Bytes)?;
+        let separator_pos = value_bytes.iter().position(|&b| b == 0u8)?;
+        let text = str::from_utf8(&value_bytes[separator_pos + 1..]).ok()?;
+        Some((&value_bytes[..separator_pos], text))
+    }
+
+    fn text_and_suffix(&self) -> Option<(&str, &[u8])> {
+        let mut value_bytes = self.value_bytes_of_type(Type::Bytes)?;
+        let text_len = VInt::deserialize_u64(&mut value_bytes).ok()? as usize;
+        if text_len > value_bytes.len() {
+            return None;
+        }
+        let (text_bytes, suffix) = value_bytes.split_at(text_len);
+        Some((str::from_utf8(text_bytes).ok()?, suffix))
+    }
+
+    /// Returns the value bytes if the term is of type `typ`.
+    ///
+    /// Returns `None` for other types, and for malformed terms with an invalid type code or
+    /// too short to hold one.
+    fn value_bytes_of_type(&self, typ: Type) -> Option<&[u8]> {
+        if self.value().try_typ()? != typ {
+            return None;
+        }
+        self.0.as_ref().get(TERM_METADATA_LENGTH..)
     }
 
     /// Returns the serialized representation of the value.
@@ -259,8 +941,23 @@ where B: AsRef<[u8]>
 
     /// Returns the value of the term.
     /// address or JSON path + value. (this does not include the field.)
+    ///
+    /// The value bytes are empty if the term is shorter than the field id, so that the checked
+    /// accessors of [`ValueBytes`] return `None` instead of panicking on invalid terms.
     pub fn value(&self) -> ValueBytes<&[u8]> {
-        ValueBytes::wrap(&self.0.as_ref()[4..])
+        ValueBytes::wrap(self.0.as_ref().get(4..).unwrap_or_default())
+    }
+
+    /// Compares the values of two terms, ignoring their field.
+    ///
+    /// This assumes that both terms belong to the same field. The type code and the value bytes
+    /// are compared bytewise, which is the order of the term dictionary. A malformed term
+    /// shorter than the field id has an empty value, which sorts first.
+    pub fn cmp_value<B2>(&self, other: &Term<B2>) -> Ordering
+    where B2: AsRef<[u8]> {
+        self.value()
+            .as_serialized()
+            .cmp(other.value().as_serialized())
     }
 
     /// Returns the serialized representation of Term.
@@ -302,10 +999,20 @@ where B: AsRef<[u8]>
     }
 
     /// Return the type of the term.
+    ///
+    /// # Panics
+    /// Panics if the value bytes are empty or start with an invalid type code.
+    /// See [`ValueBytes::try_typ`] for a checked version.
     pub fn typ(&self) -> Type {
         Type::from_code(self.typ_code()).expect("The term has an invalid type code")
     }
 
+    /// Return the type of the term, or `None` if the value bytes are empty or start with an
+    /// invalid type code.
+    pub fn try_typ(&self) -> Option<Type> {
+        self.0.as_ref().first().copied().and_then(Type::from_code)
+    }
+
     /// Returns the `u64` value stored in a term.
     ///
     /// Returns `None` if the term is not of the u64 type, or if the term byte representation
@@ -315,7 +1022,7 @@ where B: AsRef<[u8]>
     }
 
     fn get_fast_type<T: FastValue>(&self) -> Option<T> {
-        if self.typ() != T::to_type() {
+        if self.try_typ() != Some(T::to_type()) {
             return None;
         }
         let value_bytes = self.value_bytes();
@@ -360,7 +1067,7 @@ where B: AsRef<[u8]>
     /// Returns `None` if the field is not of string type
     /// or if the bytes are not valid utf-8.
     pub fn as_str(&self) -> Option<&str> {
-        if self.typ() != Type::Str {
+        if self.try_typ() != Some(Type::Str) {
             return None;
         }
         str::from_utf8(self.value_bytes()).ok()
@@ -371,7 +1078,7 @@ where B: AsRef<[u8]>
     /// Returns `None` if the field is not of facet type
     /// or if the bytes are not valid utf-8.
     pub fn as_facet(&self) -> Option<Facet> {
-        if self.typ() != Type::Facet {
+        if self.try_typ() != Some(Type::Facet) {
             return None;
         }
         let facet_encode_str = str::from_utf8(self.value_bytes()).ok()?;
@@ -382,7 +1089,7 @@ where B: AsRef<[u8]>
     ///
     /// Returns `None` if the field is not of bytes type.
     pub fn as_bytes(&self) -> Option<&[u8]> {
-        if self.typ() != Type::Bytes {
+        if self.try_typ() != Some(Type::Bytes) {
             return None;
         }
         Some(self.value_bytes())
@@ -390,7 +1097,7 @@ where B: AsRef<[u8]>
 
     /// Returns a `Ipv6Addr` value from the term.
     pub fn as_ip_addr(&self) -> Option<Ipv6Addr> {
-        if self.typ() != Type::IpAddr {
+        if self.try_typ() != Some(Type::IpAddr) {
             return None;
         }
         let ip_u128 = u128::from_be_bytes(self.value_bytes().try_into().ok()?);
@@ -403,7 +1110,7 @@ where B: AsRef<[u8]>
     pub fn json_path_type(&self) -> Option<Type> {
         let json_value_bytes = self.as_json_value_bytes()?;
 
-        Some(json_value_bytes.typ())
+        json_value_bytes.try_typ()
     }
 
     /// Returns the json path bytes (including the JSON_END_OF_PATH byte),
@@ -411,7 +1118,7 @@ where B: AsRef<[u8]>
     ///
     /// Returns `None` if the value is not JSON.
     pub(crate) fn as_json(&self) -> Option<(&[u8], ValueBytes<&[u8]>)> {
-        if self.typ() != Type::Json {
+        if self.try_typ() != Some(Type::Json) {
             return None;
         }
         let bytes = self.value_bytes();
@@ -426,7 +1133,7 @@ where B: AsRef<[u8]>
     ///
     /// Returns `None` if the value is not JSON.
     pub(crate) fn as_json_value_bytes(&self) -> Option<ValueBytes<&[u8]>> {
-        if self.typ() != Type::Json {
+        if self.try_typ() != Some(Type::Json) {
             return None;
         }
         let bytes = self.value_bytes();
@@ -447,6 +1154,31 @@ where B: AsRef<[u8]>
         self.0.as_ref()
     }
 
+    fn readable_value(&self) -> Option<String> {
+        match self.try_typ()? {
+            Type::Str => self.as_str().map(|text| format!("{text:?}")),
+            Type::U64 => self.as_u64().map(|val| val.to_string()),
+            Type::I64 => self.as_i64().map(|val| val.to_string()),
+            Type::F64 => self.as_f64().map(|val| val.to_string()),
+            Type::Bool => self.as_bool().map(|val| val.to_string()),
+            Type::Date => self
+                .as_date()
+                .and_then(|date| date.into_utc().format(&Rfc3339).ok()),
+            Type::Facet => self.as_facet().map(|facet| facet.to_string()),
+            Type::Bytes => self.as_bytes().map(to_hex),
+            Type::Json => {
+                let (path_bytes, sub_value_bytes) = self.as_json()?;
+                let path = str::from_utf8(&path_bytes[..path_bytes.len() - 1]).ok()?;
+                let path_pretty = path.replace(JSON_PATH_SEGMENT_SEP_STR, ".");
+                let sub_value = sub_value_bytes
+                    .readable_value()
+                    .unwrap_or_else(|| to_hex(sub_value_bytes.as_serialized()));
+                Some(format!("{path_pretty}={sub_value}"))
+            }
+            Type::IpAddr => self.as_ip_addr().map(|ip_addr| ip_addr.to_string()),
+        }
+    }
+
     fn debug_value_bytes(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let typ = self.typ();
         write!(f, "type={typ:?}, ")?;
@@ -498,7 +1230,7 @@ where B: AsRef<[u8]>
 impl<B> Ord for Term<B>
 where B: AsRef<[u8]>
 {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    fn cmp(&self, other: &Self) -> Ordering {
         self.serialized_term().cmp(other.serialized_term())
     }
 }
@@ -506,7 +1238,7 @@ where B: AsRef<[u8]>
 impl<B> PartialOrd for Term<B>
 where B: AsRef<[u8]>
 {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
@@ -529,6 +1261,99 @@ where B: AsRef<[u8]>
     }
 }
 
+/// Borrows the serialized representation of the term, as returned by
+/// [`Term::serialized_term`], which includes the field and the type code.
+///
+/// This is consistent with `Hash` and `Eq`, so that a `HashMap<Term, _>` can be queried with
+/// the serialized bytes of a term.
+impl<B> Borrow<[u8]> for Term<B>
+where B: AsRef<[u8]>
+{
+    fn borrow(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+/// Computes the geohash of a point, by alternately bisecting the longitude and the
+/// latitude ranges. Each character encodes 5 bisections.
+fn geohash(lat: f64, lon: f64, precision: usize) -> String {
+    let mut lat_range = (-90.0, 90.0);
+    let mut lon_range = (-180.0, 180.0);
+    let mut geohash = String::with_capacity(precision);
+    let mut char_id = 0usize;
+    let mut num_bits = 0;
+    let mut is_lon = true;
+    while geohash.len() < precision {
+        let (range, val): (&mut (f64, f64), f64) = if is_lon {
+            (&mut lon_range, lon)
+        } else {
+            (&mut lat_range, lat)
+        };
+        let mid = (range.0 + range.1) / 2.0;
+        char_id <<= 1;
+        if val >= mid {
+            char_id |= 1;
+            range.0 = mid;
+        } else {
+            range.1 = mid;
+        }
+        is_lon = !is_lon;
+        num_bits += 1;
+        if num_bits == 5 {
+            geohash.push(GEOHASH_BASE32[char_id] as char);
+            char_id = 0;
+            num_bits = 0;
+        }
+    }
+    geohash
+}
+
+/// Concatenates the tokens emitted by `normalizer` for `text`.
+fn normalize_text(text: &str, normalizer: &mut TextAnalyzer) -> String {
+    let mut normalized_text = String::with_capacity(text.len());
+    let mut token_stream = normalizer.token_stream(text);
+    token_stream.process(&mut |token| normalized_text.push_str(&token.text));
+    normalized_text
+}
+
+/// Returns the exclusive upper bound of the terms whose value starts with `prefix`.
+///
+/// This is the prefix with its last byte incremented, after dropping the trailing
+/// `0xFF` bytes that cannot be incremented. If no byte can be incremented, all of the
+/// terms of the field are greater than the prefix and the range is unbounded.
+fn prefix_upper_bound(typ: Type, field: Field, prefix: &[u8]) -> Bound<Term> {
+    let mut upper_bound = prefix.to_vec();
+    while let Some(last_byte) = upper_bound.pop() {
+        if last_byte != u8::MAX {
+            upper_bound.push(last_byte + 1);
+            return Bound::Excluded(Term::with_bytes_and_field_and_payload(
+                typ,
+                field,
+                &upper_bound,
+            ));
+        }
+    }
+    Bound::Unbounded
+}
+
+/// `-0.0` and `0.0` compare equal but have different bit patterns.
+/// We map both to `0.0` so that they end up in the same term.
+pub(crate) fn normalize_zero(val: f64) -> f64 {
+    if val == 0.0 {
+        0.0
+    } else {
+        val
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn opt_to_string<T: fmt::Display>(val_opt: Option<T>) -> String {
+    val_opt.map(|val| val.to_string()).unwrap_or_default()
+}
+
 fn write_opt<T: std::fmt::Debug>(f: &mut fmt::Formatter, val_opt: Option<T>) -> fmt::Result {
     if let Some(val) = val_opt {
         write!(f, "{val:?}")?;
@@ -553,6 +1378,7 @@ where B: AsRef<[u8]>
 mod tests {
 
     use crate::schema::*;
+    use crate::DateTime;
 
     #[test]
     pub fn test_term_str() {
@@ -588,6 +1414,90 @@ mod tests {
         assert_eq!(term.value().as_u64(), Some(983u64))
     }
 
+    #[test]
+    pub fn test_term_f64() {
+        let mut schema_builder = Schema::builder();
+        let f64_field = schema_builder.add_f64_field("f64", INDEXED);
+        let vals = [
+            f64::NEG_INFINITY,
+            -1.0e10f64,
+            -2.5f64,
+            -f64::MIN_POSITIVE,
+            0.0f64,
+            f64::MIN_POSITIVE,
+            0.5f64,
+            2.5f64,
+            1.0e10f64,
+            f64::INFINITY,
+        ];
+        let terms: Vec<Term> = vals
+            .iter()
+            .map(|&val| Term::from_field_f64(f64_field, val))
+            .collect();
+        for (term, &val) in terms.iter().zip(vals.iter()) {
+            assert_eq!(term.field(), f64_field);
+            assert_eq!(term.typ(), Type::F64);
+            assert_eq!(term.serialized_term().len(), FAST_VALUE_TERM_LEN);
+            assert_eq!(term.value().as_f64(), Some(val));
+        }
+        assert!(terms.windows(2).all(|terms| terms[0] < terms[1]));
+    }
+
+    #[test]
+    pub fn test_term_date() {
+        let mut schema_builder = Schema::builder();
+        let date_field = schema_builder.add_date_field("date", INDEXED);
+        let timestamps_secs = [-86_400i64 * 365, -1i64, 0i64, 1i64, 1_700_000_000i64];
+        let terms: Vec<Term> = timestamps_secs
+            .iter()
+            .map(|&timestamp_secs| {
+                Term::from_field_date(date_field, DateTime::from_timestamp_secs(timestamp_secs))
+            })
+            .collect();
+        for (term, &timestamp_secs) in terms.iter().zip(timestamps_secs.iter()) {
+            assert_eq!(term.field(), date_field);
+            assert_eq!(term.typ(), Type::Date);
+            assert_eq!(term.serialized_term().len(), FAST_VALUE_TERM_LEN);
+            assert_eq!(
+                term.value().as_date(),
+                Some(DateTime::from_timestamp_secs(timestamp_secs))
+            );
+        }
+        assert!(terms.windows(2).all(|terms| terms[0] < terms[1]));
+        // Dates are truncated to the indexed precision.
+        let date_with_millis = DateTime::from_timestamp_millis(1_700_000_000_123i64);
+        assert_eq!(
+            Term::from_field_date(date_field, date_with_millis),
+            terms[4]
+        );
+    }
+
+    #[test]
+    pub fn test_term_type_tag() {
+        let mut schema_builder = Schema::builder();
+        let field = schema_builder.add_u64_field("field", INDEXED);
+        let u64_term = Term::from_field_u64(field, 983u64);
+        let bytes_term = Term::from_field_bytes(field, &983u64.to_be_bytes());
+        // Same value bytes, but the type tag makes the terms distinct.
+        assert_eq!(
+            u64_term.serialized_value_bytes(),
+            bytes_term.serialized_value_bytes()
+        );
+        assert_ne!(u64_term, bytes_term);
+        assert_eq!(u64_term.typ(), Type::U64);
+        assert_eq!(bytes_term.typ(), Type::Bytes);
+        assert_eq!(u64_term.serialized_term()[4], Type::U64.to_code());
+        assert_eq!(u64_term.value().as_u64(), Some(983u64));
+        assert_eq!(u64_term.value().as_bytes(), None);
+        assert_eq!(bytes_term.value().as_u64(), None);
+        assert_eq!(
+            bytes_term.value().as_bytes(),
+            Some(&983u64.to_be_bytes()[..])
+        );
+        // Within a field and type, the byte order matches the value order.
+        assert!(u64_term < Term::from_field_u64(field, 984u64));
+    }
+
     #[test]
     pub fn test_term_bool() {
         let mut schema_builder = Schema::builder();
@@ -596,6 +1506,915 @@ mod tests {
         assert_eq!(term.field(), bool_field);
         assert_eq!(term.typ(), Type::Bool);
         assert_eq!(term.serialized_term().len(), FAST_VALUE_TERM_LEN);
-        assert_eq!(term.value().as_bool(), Some(true))
+        assert_eq!(term.value().as_bool(), Some(true));
+        let false_term = Term::from_field_bool(bool_field, false);
+        assert_eq!(false_term.value().as_bool(), Some(false));
+        assert!(false_term < term);
+    }
+
+    #[test]
+    pub fn test_term_flags() {
+        let mut schema_builder = Schema::builder();
+        let flags_field = schema_builder.add_u64_field("flags", INDEXED);
+        let mut term = Term::from_field_flags(flags_field, 0u64);
+        term.set_flag(0, true);
+        term.set_flag(2, true);
+        term.set_flag(63, true);
+        assert_eq!(term.get_flag(0), Some(true));
+        assert_eq!(term.get_flag(1), Some(false));
+        assert_eq!(term.get_flag(2), Some(true));
+        assert_eq!(term.get_flag(63), Some(true));
+        assert_eq!(term.get_flag(64), None);
+        term.set_flag(63, false);
+        assert_eq!(term.get_flag(63), Some(false));
+        assert_eq!(term.typ(), Type::U64);
+        assert_eq!(term.serialized_value_bytes(), 0b101u64.to_be_bytes());
+        assert_eq!(term, Term::from_field_u64(flags_field, 0b101u64));
+        let text_term = Term::from_field_text(flags_field, "abc");
+        assert_eq!(text_term.get_flag(0), None);
+    }
+
+    #[test]
+    pub fn test_term_f64_negative_zero() {
+        let mut schema_builder = Schema::builder();
+        let f64_field = schema_builder.add_f64_field("f64", INDEXED);
+        let neg_zero_term = Term::from_field_f64(f64_field, -0.0);
+        assert_eq!(neg_zero_term, Term::from_field_f64(f64_field, 0.0));
+        let mut term = Term::from_field_f64(f64_field, 1.0);
+        term.set_f64(-0.0);
+        assert_eq!(term, neg_zero_term);
+        assert!(term.value().as_f64().unwrap().is_sign_positive());
+
+        let json_field = schema_builder.add_json_field("json", STRING);
+        let json_neg_zero_term =
+            Term::from_field_json_path(json_field, "val", &json!(-0.0)).unwrap();
+        assert_eq!(
+            json_neg_zero_term,
+            Term::from_field_json_path(json_field, "val", &json!(0.0)).unwrap()
+        );
+    }
+
+    #[test]
+    pub fn test_term_query_string_round_trip() {
+        let mut schema_builder = Schema::builder();
+        let title_field = schema_builder.add_text_field("title", STRING);
+        let count_field = schema_builder.add_u64_field("count", INDEXED);
+        let schema = schema_builder.build();
+
+        let text_term = Term::from_field_text(title_field, "hello: world/");
+        let text_query_str = text_term.to_query_string(&schema);
+        assert_eq!(text_query_str, "title/str:hello: world/");
+        assert_eq!(
+            Term::from_query_string(&text_query_str, &schema).unwrap(),
+            text_term
+        );
+
+        let count_term = Term::from_field_u64(count_field, 983);
+        let count_query_str = count_term.to_query_string(&schema);
+        assert_eq!(count_query_str, "count/u64:983");
+        assert_eq!(
+            Term::from_query_string(&count_query_str, &schema).unwrap(),
+            count_term
+        );
+    }
+
+    #[test]
+    pub fn test_term_query_string_errors() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_u64_field("count", INDEXED);
+        let schema = schema_builder.build();
+        assert!(matches!(
+            Term::from_query_string("unknown/u64:983", &schema),
+            Err(crate::TantivyError::FieldNotFound(field_name)) if field_name == "unknown"
+        ));
+        assert!(matches!(
+            Term::from_query_string("count/str:983", &schema),
+            Err(crate::TantivyError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            Term::from_query_string("count/u64:abc", &schema),
+            Err(crate::TantivyError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_term_hashmap_lookup_by_bytes() {
+        use std::collections::HashMap;
+
+        let mut schema_builder = Schema::builder();
+        let title_field = schema_builder.add_text_field("title", STRING);
+        let count_field = schema_builder.add_u64_field("count", INDEXED);
+        let mut term_ids: HashMap<Term, usize> = HashMap::new();
+        term_ids.insert(Term::from_field_text(title_field, "hello"), 0);
+        term_ids.insert(Term::from_field_u64(count_field, 983), 1);
+
+        let hello_bytes = Term::from_field_text(title_field, "hello")
+            .serialized_term()
+            .to_vec();
+        assert_eq!(term_ids.get(&hello_bytes[..]), Some(&0));
+        let count_term = Term::from_field_u64(count_field, 983);
+        let borrowed_term = Term::wrap(count_term.serialized_term());
+        assert_eq!(term_ids.get(borrowed_term.serialized_term()), Some(&1));
+        // same value bytes, but different field
+        let other_field_bytes = Term::from_field_text(count_field, "hello")
+            .serialized_term()
+            .to_vec();
+        assert_eq!(term_ids.get(&other_field_bytes[..]), None);
+    }
+
+    #[test]
+    pub fn test_term_cmp_value() {
+        use std::cmp::Ordering;
+
+        let mut schema_builder = Schema::builder();
+        let first_field = schema_builder.add_u64_field("first", INDEXED);
+        let second_field = schema_builder.add_u64_field("second", INDEXED);
+        let small = Term::from_field_u64(second_field, 3);
+        let large = Term::from_field_u64(first_field, 1_000);
+        // the derived order compares the field first
+        assert_eq!(small.cmp(&large), Ordering::Greater);
+        assert_eq!(small.cmp_value(&large), Ordering::Less);
+        assert_eq!(large.cmp_value(&small), Ordering::Greater);
+        assert_eq!(
+            small.cmp_value(&Term::wrap(small.serialized_term())),
+            Ordering::Equal
+        );
+        assert_eq!(
+            small.cmp_value(&Term::from_field_u64(first_field, 3)),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    pub fn test_term_value_accessors_on_invalid_terms() {
+        use std::cmp::Ordering;
+
+        // truncated u64 value
+        let truncated = Term::wrap(&[0u8, 0, 0, 1, b'u', 1, 2][..]);
+        assert_eq!(truncated.value().try_typ(), Some(Type::U64));
+        assert_eq!(truncated.value().as_u64(), None);
+        // invalid type code
+        let invalid_type = Term::wrap(&[0u8, 0, 0, 1, b'?', 0, 0, 0, 0, 0, 0, 0, 1][..]);
+        assert_eq!(invalid_type.value().try_typ(), None);
+        assert_eq!(invalid_type.value().as_u64(), None);
+        assert_eq!(invalid_type.value().as_str(), None);
+        // no type code
+        let no_type = Term::wrap(&[0u8, 0, 0, 1][..]);
+        assert_eq!(no_type.value().try_typ(), None);
+        assert_eq!(no_type.value().as_i64(), None);
+        // shorter than the field id
+        let too_short = Term::wrap(&[0u8, 0][..]);
+        assert_eq!(too_short.value().as_u64(), None);
+        // json path without a value type
+        let json_path_only = Term::wrap(&b"\x00\x00\x00\x01jcolor\x00"[..]);
+        assert_eq!(json_path_only.value().json_path_type(), None);
+
+        // the accessors of specific term encodings
+        for term in [&invalid_type, &no_type, &too_short] {
+            assert_eq!(term.lang_code(), None);
+            assert_eq!(term.lang_text(), None);
+            assert_eq!(term.interval(), None);
+            assert_eq!(term.varint(), None);
+            assert_eq!(term.geohash(), None);
+            assert_eq!(term.collation_key(), None);
+            assert_eq!(term.collated_text(), None);
+            assert_eq!(term.text_part(), None);
+            assert_eq!(term.suffix(), None);
+        }
+        let valid = Term::from_field_u64(Field::from_field_id(1), 1);
+        assert_eq!(too_short.cmp_value(&valid), Ordering::Less);
+        assert_eq!(valid.cmp_value(&too_short), Ordering::Greater);
+        assert_eq!(too_short.cmp_value(&too_short), Ordering::Equal);
+        assert_eq!(no_type.cmp_value(&too_short), Ordering::Equal);
+
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_u64_field("count", INDEXED);
+        schema_builder.add_u64_field("flags", INDEXED);
+        let schema = schema_builder.build();
+        assert_eq!(
+            invalid_type.to_query_string(&schema),
+            "flags/?:PwAAAAAAAAAB"
+        );
+        assert_eq!(no_type.to_query_string(&schema), "flags/?:");
+        assert!(Term::from_query_string(&invalid_type.to_query_string(&schema), &schema).is_err());
+    }
+
+    #[test]
+    pub fn test_term_field_bytes() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("text", STRING);
+        let title_field = schema_builder.add_text_field("title", STRING);
+        let term = Term::from_field_text(title_field, "hello");
+        assert_eq!(term.field_bytes(), &[0u8, 0u8, 0u8, 1u8]);
+        assert_eq!(&term.serialized_term()[..4], term.field_bytes());
+        let borrowed_term = Term::wrap(term.serialized_term());
+        assert_eq!(borrowed_term.field_bytes(), term.field_bytes());
+        assert_eq!(borrowed_term.field(), title_field);
+    }
+
+    #[test]
+    pub fn test_term_clear_and_reuse() {
+        let mut schema_builder = Schema::builder();
+        let title_field = schema_builder.add_text_field("title", STRING);
+        let count_field = schema_builder.add_u64_field("count", INDEXED);
+        let mut term = Term::from_field_text(title_field, "a rather long text value");
+        let capacity = term.0.capacity();
+        for i in 0..3u64 {
+            term.clear();
+            assert!(term.is_empty());
+            term.set_field(count_field);
+            term.clear_with_type(Type::U64);
+            term.set_u64(i);
+            assert_eq!(term, Term::from_field_u64(count_field, i));
+
+            term.clear();
+            term.set_field(title_field);
+            term.clear_with_type(Type::Str);
+            term.set_text("abc");
+            assert_eq!(term, Term::from_field_text(title_field, "abc"));
+            assert_eq!(
+                term.serialized_term(),
+                Term::from_field_text(title_field, "abc").serialized_term()
+            );
+        }
+        assert_eq!(term.0.capacity(), capacity);
+    }
+
+    #[test]
+    pub fn test_term_to_readable_string() {
+        let mut schema_builder = Schema::builder();
+        let title_field = schema_builder.add_text_field("title", STRING);
+        let count_field = schema_builder.add_i64_field("count", INDEXED);
+        let bytes_field = schema_builder.add_bytes_field("bytes", INDEXED);
+        let schema = schema_builder.build();
+
+        assert_eq!(
+            Term::from_field_text(title_field, "hello").to_readable_string(&schema),
+            "title:\"hello\""
+        );
+        assert_eq!(
+            Term::from_field_i64(count_field, -3).to_readable_string(&schema),
+            "count:-3"
+        );
+        assert_eq!(
+            Term::from_field_bytes(bytes_field, &[0u8, 171u8]).to_readable_string(&schema),
+            "bytes:00ab"
+        );
+        // the term type does not match the schema
+        assert_eq!(
+            Term::from_field_u64(title_field, 1).to_readable_string(&schema),
+            "title:0000000000000001"
+        );
+        // invalid utf-8
+        let mut term = Term::from_field_text(title_field, "");
+        term.append_bytes(&[255u8]);
+        assert_eq!(term.to_readable_string(&schema), "title:ff");
+        // unknown field
+        assert_eq!(
+            Term::from_field_u64(Field::from_field_id(7), 1).to_readable_string(&schema),
+            "7:0000000000000001"
+        );
+        // malformed terms
+        assert_eq!(
+            Term::wrap(&[0u8, 0, 0, 0, b'?', 1][..]).to_readable_string(&schema),
+            "title:01"
+        );
+        assert_eq!(
+            Term::wrap(&[0u8, 0, 0, 1][..]).to_readable_string(&schema),
+            "count:"
+        );
+        assert_eq!(
+            Term::wrap(&[0u8, 2][..]).to_readable_string(&schema),
+            "?:0002"
+        );
+    }
+
+    #[test]
+    pub fn test_term_to_readable_string_json() {
+        let mut schema_builder = Schema::builder();
+        let json_field = schema_builder.add_json_field("json", STRING);
+        let schema = schema_builder.build();
+
+        let term = Term::from_field_json_path(json_field, "color", &json!("red")).unwrap();
+        assert_eq!(term.to_readable_string(&schema), "json:color=\"red\"");
+        // json path without a value type
+        let json_path_only = Term::wrap(&b"\x00\x00\x00\x00jcolor\x00"[..]);
+        assert_eq!(json_path_only.to_readable_string(&schema), "json:color=");
+        // json path with an invalid value type
+        let invalid_type = Term::wrap(&b"\x00\x00\x00\x00jcolor\x00?ab"[..]);
+        assert_eq!(
+            invalid_type.to_readable_string(&schema),
+            "json:color=3f6162"
+        );
+    }
+
+    #[test]
+    pub fn test_term_text_with_suffix() {
+        let mut schema_builder = Schema::builder();
+        let bytes_field = schema_builder.add_bytes_field("bytes", INDEXED);
+        let term = Term::from_field_text_with_suffix(bytes_field, "hello", &[1u8, 255u8]);
+        assert_eq!(term.typ(), Type::Bytes);
+        assert_eq!(term.text_part(), Some("hello"));
+        assert_eq!(term.suffix(), Some(&[1u8, 255u8][..]));
+        assert_eq!(term.value().as_str(), None);
+
+        let no_suffix_term = Term::from_field_text_with_suffix(bytes_field, "hello", &[]);
+        assert_eq!(no_suffix_term.text_part(), Some("hello"));
+        assert_eq!(no_suffix_term.suffix(), Some(&[][..]));
+
+        let long_text = "a".repeat(300);
+        let long_term = Term::from_field_text_with_suffix(bytes_field, &long_text, b"v2");
+        assert_eq!(long_term.text_part(), Some(long_text.as_str()));
+        assert_eq!(long_term.suffix(), Some(&b"v2"[..]));
+
+        let text_term = Term::from_field_text(bytes_field, "hello");
+        assert_eq!(text_term.text_part(), None);
+        assert_eq!(text_term.suffix(), None);
+    }
+
+    #[test]
+    pub fn test_term_empty_str() {
+        let mut schema_builder = Schema::builder();
+        let title_field = schema_builder.add_text_field("title", STRING);
+        let term = Term::from_field_text(title_field, "");
+        assert!(term.is_empty());
+        assert_eq!(term.len_bytes(), 0);
+        assert_eq!(term.serialized_term().len(), 4 + 1);
+        assert_eq!(term.value().as_str(), Some(""));
+        assert_ne!(term, Term::from_field_text(title_field, " "));
+    }
+
+    #[test]
+    pub fn test_term_from_column_value() {
+        use columnar::{ColumnType, MonotonicallyMappableToU64};
+        let mut schema_builder = Schema::builder();
+        let u64_field = schema_builder.add_u64_field("u64", INDEXED);
+        let i64_field = schema_builder.add_i64_field("i64", INDEXED);
+        let f64_field = schema_builder.add_f64_field("f64", INDEXED);
+
+        let u64_term = Term::from_field_column_value(u64_field, ColumnType::U64, 983u64);
+        assert_eq!(u64_term, Some(Term::from_field_u64(u64_field, 983u64)));
+
+        let i64_terms: Vec<Term> = [-10i64, -1i64, 0i64, 5i64]
+            .iter()
+            .map(|val| {
+                let term = Term::from_field_column_value(i64_field, ColumnType::I64, val.to_u64())
+                    .unwrap();
+                assert_eq!(term, Term::from_field_i64(i64_field, *val));
+                term
+            })
+            .collect();
+        assert!(i64_terms.windows(2).all(|terms| terms[0] < terms[1]));
+
+        let f64_terms: Vec<Term> = [-10.5f64, -0.5f64, 0.0f64, 2.5f64]
+            .iter()
+            .map(|val| {
+                let term = Term::from_field_column_value(f64_field, ColumnType::F64, val.to_u64())
+                    .unwrap();
+                assert_eq!(term.value().as_f64(), Some(*val));
+                term
+            })
+            .collect();
+        assert!(f64_terms.windows(2).all(|terms| terms[0] < terms[1]));
+
+        assert!(Term::from_field_column_value(u64_field, ColumnType::Str, 0u64).is_none());
+    }
+
+    #[test]
+    pub fn test_term_prefix_range() {
+        use std::ops::Bound;
+
+        let mut schema_builder = Schema::builder();
+        let title_field = schema_builder.add_text_field("title", STRING);
+        let in_range = |prefix: &str, text: &str| {
+            let (lower_bound, upper_bound) = Term::prefix_range(title_field, prefix);
+            let term = Term::from_field_text(title_field, text);
+            let above_lower = match lower_bound {
+                Bound::Included(lower_bound) => lower_bound <= term,
+                _ => panic!("Lower bound should be inclusive"),
+            };
+            let below_upper = match upper_bound {
+                Bound::Excluded(upper_bound) => term < upper_bound,
+                Bound::Unbounded => true,
+                Bound::Included(_) => panic!("Upper bound should be exclusive"),
+            };
+            above_lower && below_upper
+        };
+        assert!(in_range("abc", "abc"));
+        assert!(in_range("abc", "abcdef"));
+        assert!(!in_range("abc", "abd"));
+        assert!(!in_range("abc", "ab"));
+
+        // multi-byte utf-8: é is [0xC3, 0xA9], and ê is [0xC3, 0xAA]
+        assert!(in_range("caf\u{e9}", "caf\u{e9}"));
+        assert!(in_range("caf\u{e9}", "caf\u{e9}ine"));
+        assert!(!in_range("caf\u{e9}", "caf\u{ea}"));
+        assert!(!in_range("caf\u{e9}", "cafe"));
+        // 本 is [0xE6, 0x9C, 0xAC], and 札 is [0xE6, 0x9C, 0xAD]
+        assert!(in_range("日本", "日本語"));
+        assert!(!in_range("日本", "日"));
+        assert!(!in_range("日本", "日札"));
+        // U+10FFFF is [0xF4, 0x8F, 0xBF, 0xBF]
+        assert!(in_range("\u{10FFFF}", "\u{10FFFF}\u{10FFFF}"));
+        let (_, upper_bound) = Term::prefix_range(title_field, "\u{10FFFF}");
+        match upper_bound {
+            Bound::Excluded(upper_bound) => {
+                assert_eq!(
+                    upper_bound.serialized_value_bytes(),
+                    &[0xF4, 0x8F, 0xBF, 0xC0]
+                )
+            }
+            _ => panic!("Upper bound should be exclusive"),
+        }
+
+        // empty prefix
+        assert!(matches!(
+            Term::prefix_range(title_field, ""),
+            (Bound::Included(_), Bound::Unbounded)
+        ));
+        assert!(in_range("", "anything"));
+    }
+
+    #[test]
+    pub fn test_term_text_prefix_range_normalized() {
+        use std::ops::Bound;
+
+        use crate::tokenizer::{AsciiFoldingFilter, LowerCaser, RawTokenizer, TextAnalyzer};
+
+        let mut schema_builder = Schema::builder();
+        let title_field = schema_builder.add_text_field("title", STRING);
+        let mut normalizer = TextAnalyzer::builder(RawTokenizer::default())
+            .filter(LowerCaser)
+            .filter(AsciiFoldingFilter)
+            .build();
+        let (lower_bound, upper_bound) =
+            Term::text_prefix_range_normalized(title_field, "StraßE", &mut normalizer);
+        let lower_bound = match lower_bound {
+            Bound::Included(term) => term,
+            _ => panic!("Lower bound should be inclusive"),
+        };
+        let upper_bound = match upper_bound {
+            Bound::Excluded(term) => term,
+            _ => panic!("Upper bound should be exclusive"),
+        };
+        assert_eq!(lower_bound.value().as_str(), Some("strasse"));
+        assert_eq!(upper_bound.serialized_value_bytes(), b"strassf");
+        let in_range = |text: &str| {
+            let term = Term::from_field_text(title_field, text);
+            lower_bound <= term && term < upper_bound
+        };
+        assert!(in_range("strasse"));
+        assert!(in_range("strassenbahn"));
+        assert!(!in_range("strass"));
+        assert!(!in_range("strassf"));
+        assert!(!in_range("straße"));
+
+        let (_, upper_bound) = Term::text_prefix_range_normalized(title_field, "", &mut normalizer);
+        assert!(matches!(upper_bound, Bound::Unbounded));
+    }
+
+    #[test]
+    pub fn test_term_from_field_text_normalized() {
+        use crate::tokenizer::{LowerCaser, RawTokenizer, TextAnalyzer};
+
+        let mut schema_builder = Schema::builder();
+        let title_field = schema_builder.add_text_field("title", STRING);
+        let mut normalizer = TextAnalyzer::builder(RawTokenizer::default())
+            .filter(LowerCaser)
+            .build();
+        let term = Term::from_field_text_normalized(title_field, "Hello World", &mut normalizer);
+        assert_eq!(term, Term::from_field_text(title_field, "hello world"));
+        assert_eq!(term.value().as_str(), Some("hello world"));
+    }
+
+    #[test]
+    pub fn test_term_text_lang() {
+        let mut schema_builder = Schema::builder();
+        let bytes_field = schema_builder.add_bytes_field("bytes", INDEXED);
+        let term = Term::from_field_text_lang(bytes_field, 2u16, "hello");
+        assert_eq!(term.typ(), Type::Bytes);
+        assert_eq!(term.lang_code(), Some(2u16));
+        assert_eq!(term.lang_text(), Some("hello"));
+        assert_eq!(term.serialized_value_bytes(), b"\x00\x02hello");
+
+        // The prefix range of a language does not overlap with other languages.
+        let lower_bound = Term::from_field_text_lang(bytes_field, 1u16, "ab");
+        let upper_bound = Term::from_field_text_lang(bytes_field, 1u16, "ac");
+        let in_prefix_range = |term: &Term| &lower_bound <= term && term < &upper_bound;
+        assert!(in_prefix_range(&Term::from_field_text_lang(
+            bytes_field,
+            1u16,
+            "ab"
+        )));
+        assert!(in_prefix_range(&Term::from_field_text_lang(
+            bytes_field,
+            1u16,
+            "abc"
+        )));
+        assert!(!in_prefix_range(&Term::from_field_text_lang(
+            bytes_field,
+            0u16,
+            "abc"
+        )));
+        assert!(!in_prefix_range(&Term::from_field_text_lang(
+            bytes_field,
+            2u16,
+            "abc"
+        )));
+        assert!(
+            Term::from_field_text_lang(bytes_field, 1u16, "zzz")
+                < Term::from_field_text_lang(bytes_field, 2u16, "")
+        );
+
+        assert_eq!(
+            Term::from_field_bytes(bytes_field, &[1u8]).lang_code(),
+            None
+        );
+    }
+
+    #[test]
+    pub fn test_term_with_tiebreak_suffix() {
+        let mut schema_builder = Schema::builder();
+        let count_field = schema_builder.add_u64_field("count", INDEXED);
+        let term = Term::from_field_u64(count_field, 10u64);
+        let term_tiebreak_1 = term.with_tiebreak_suffix(1u64);
+        let term_tiebreak_256 = term.with_tiebreak_suffix(256u64);
+        assert_eq!(
+            term_tiebreak_1.serialized_term().len(),
+            FAST_VALUE_TERM_LEN + 8
+        );
+        assert_eq!(
+            &term_tiebreak_1.serialized_term()[..FAST_VALUE_TERM_LEN],
+            term.serialized_term()
+        );
+        assert!(term_tiebreak_1 < term_tiebreak_256);
+        // The value still takes precedence over the tiebreak.
+        let larger_term = Term::from_field_u64(count_field, 11u64).with_tiebreak_suffix(0u64);
+        assert!(term_tiebreak_256 < larger_term);
+    }
+
+    #[test]
+    pub fn test_term_interval() {
+        let mut schema_builder = Schema::builder();
+        let interval_field = schema_builder.add_bytes_field("interval", INDEXED);
+        let term = Term::from_field_interval(interval_field, -10i64, 5i64);
+        assert_eq!(term.typ(), Type::Bytes);
+        assert_eq!(term.interval(), Some((-10i64, 5i64)));
+        assert_eq!(term.serialized_value_bytes().len(), 16);
+
+        let terms = [
+            Term::from_field_interval(interval_field, i64::MIN, 0i64),
+            Term::from_field_interval(interval_field, -10i64, -5i64),
+            Term::from_field_interval(interval_field, -10i64, 5i64),
+            Term::from_field_interval(interval_field, 3i64, 4i64),
+            Term::from_field_interval(interval_field, 3i64, i64::MAX),
+        ];
+        assert!(terms.windows(2).all(|terms| terms[0] < terms[1]));
+
+        assert_eq!(
+            Term::from_field_bytes(interval_field, &[1u8]).interval(),
+            None
+        );
+    }
+
+    #[test]
+    pub fn test_term_varint() {
+        let mut schema_builder = Schema::builder();
+        let varint_field = schema_builder.add_bytes_field("varint", INDEXED);
+        let vals = [0u64, 1u64, 255u64, 256u64, 65_535u64, 1u64 << 40, u64::MAX];
+        let terms: Vec<Term> = vals
+            .iter()
+            .map(|&val| Term::from_field_varint(varint_field, val))
+            .collect();
+        for (term, &val) in terms.iter().zip(vals.iter()) {
+            assert_eq!(term.varint(), Some(val));
+        }
+        assert!(terms.windows(2).all(|terms| terms[0] < terms[1]));
+        assert_eq!(terms[0].serialized_value_bytes(), &[0u8]);
+        assert_eq!(terms[1].serialized_value_bytes(), &[1u8, 1u8]);
+        assert_eq!(terms[3].serialized_value_bytes(), &[2u8, 1u8, 0u8]);
+        assert_eq!(terms[6].serialized_value_bytes().len(), 9);
+
+        assert_eq!(
+            Term::from_field_bytes(varint_field, &[2u8, 1u8]).varint(),
+            None
+        );
+    }
+
+    #[test]
+    pub fn test_term_geohash() {
+        let mut schema_builder = Schema::builder();
+        let geohash_field = schema_builder.add_text_field("geohash", STRING);
+        let term = Term::from_field_geohash(geohash_field, 57.64911, 10.40744, 11);
+        assert_eq!(term.typ(), Type::Str);
+        assert_eq!(term.geohash(), Some("u4pruydqqvj"));
+        for precision in 1..=12 {
+            let term = Term::from_field_geohash(geohash_field, 57.64911, 10.40744, precision);
+            assert_eq!(term.geohash().unwrap().len(), precision);
+        }
+        // The Eiffel tower and the Louvre are in the same 4-characters cell.
+        let eiffel_tower = Term::from_field_geohash(geohash_field, 48.8584, 2.2945, 6);
+        let louvre = Term::from_field_geohash(geohash_field, 48.8606, 2.3376, 6);
+        assert_eq!(eiffel_tower.geohash(), Some("u09tun"));
+        assert_eq!(louvre.geohash(), Some("u09tvn"));
+        assert_eq!(
+            eiffel_tower.geohash().unwrap()[..4],
+            louvre.geohash().unwrap()[..4]
+        );
+    }
+
+    #[test]
+    pub fn test_term_text_collated() {
+        /// Simplified german collation, where umlauts sort like their base letter.
+        struct GermanCollator;
+
+        impl Collator for GermanCollator {
+            fn sort_key(&self, text: &str) -> Vec<u8> {
+                let mut sort_key = String::with_capacity(text.len());
+                for c in text.chars().flat_map(char::to_lowercase) {
+                    match c {
+                        'ä' => sort_key.push('a'),
+                        'ö' => sort_key.push('o'),
+                        'ü' => sort_key.push('u'),
+                        'ß' => sort_key.push_str("ss"),
+                        _ => sort_key.push(c),
+                    }
+                }
+                sort_key.into_bytes()
+            }
+        }
+
+        let mut schema_builder = Schema::builder();
+        let name_field = schema_builder.add_bytes_field("name", INDEXED);
+        let collated =
+            |text: &str| Term::from_field_text_collated(name_field, text, &GermanCollator);
+        // In byte order, "ä" comes after "z".
+        assert!(Term::from_field_text(name_field, "ä") > Term::from_field_text(name_field, "z"));
+        assert!(collated("ä") < collated("z"));
+        assert!(collated("äpfel") < collated("birne"));
+        assert!(collated("apfel") < collated("äpfel"));
+        assert_eq!(collated("äpfel").collated_text(), Some("äpfel"));
+        assert_eq!(collated("äpfel").collation_key(), Some(&b"apfel"[..]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Sort keys must not contain 0u8")]
+    pub fn test_term_text_collated_sort_key_with_zero() {
+        struct IdentityCollator;
+
+        impl Collator for IdentityCollator {
+            fn sort_key(&self, text: &str) -> Vec<u8> {
+                text.as_bytes().to_vec()
+            }
+        }
+
+        let mut schema_builder = Schema::builder();
+        let name_field = schema_builder.add_bytes_field("name", INDEXED);
+        Term::from_field_text_collated(name_field, "a\0b", &IdentityCollator);
+    }
+
+    #[test]
+    pub fn test_term_decode_packed_fast_values() {
+        use columnar::MonotonicallyMappableToU64;
+
+        let mut schema_builder = Schema::builder();
+        let i64_field = schema_builder.add_i64_field("i64", INDEXED);
+        let other_field = schema_builder.add_i64_field("other", INDEXED);
+        let vals = [-100i64, -1i64, 0i64, 3i64, i64::MAX];
+        let mut packed_terms = Vec::new();
+        for &val in &vals {
+            packed_terms.extend_from_slice(Term::from_field_i64(i64_field, val).serialized_term());
+        }
+        let decoded: Vec<i64> = Term::decode_packed_fast_values(&packed_terms)
+            .unwrap()
+            .map(i64::from_u64)
+            .collect();
+        let expected: Vec<i64> = packed_terms
+            .chunks(FAST_VALUE_TERM_LEN)
+            .map(|term_bytes| Term::wrap(term_bytes).value().as_i64().unwrap())
+            .collect();
+        assert_eq!(decoded, expected);
+        assert_eq!(decoded, vals);
+
+        assert_eq!(Term::decode_packed_fast_values(&[]).unwrap().count(), 0);
+        assert!(Term::decode_packed_fast_values(&packed_terms[1..]).is_none());
+        let mut mixed_fields = packed_terms.clone();
+        mixed_fields.extend_from_slice(Term::from_field_i64(other_field, 1i64).serialized_term());
+        assert!(Term::decode_packed_fast_values(&mixed_fields).is_none());
+        let text_term = Term::from_field_text(i64_field, "12345678");
+        assert!(Term::decode_packed_fast_values(text_term.serialized_term()).is_none());
+    }
+
+    #[test]
+    pub fn test_term_from_field_value() {
+        let mut schema_builder = Schema::builder();
+        let title_field = schema_builder.add_text_field("title", STRING);
+        let count_field = schema_builder.add_u64_field("count", INDEXED);
+        let json_field = schema_builder.add_json_field("json", STRING);
+        let schema = schema_builder.build();
+        let title_type = schema.get_field_entry(title_field).field_type();
+        let count_type = schema.get_field_entry(count_field).field_type();
+        let json_type = schema.get_field_entry(json_field).field_type();
+
+        let title_term =
+            Term::from_field_value(title_field, title_type, &Value::Str("hello".to_string()))
+                .unwrap();
+        assert_eq!(title_term, Term::from_field_text(title_field, "hello"));
+        let count_term =
+            Term::from_field_value(count_field, count_type, &Value::U64(983u64)).unwrap();
+        assert_eq!(count_term, Term::from_field_u64(count_field, 983u64));
+
+        assert!(matches!(
+            Term::from_field_value(count_field, count_type, &Value::I64(983i64)),
+            Err(crate::TantivyError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            Term::from_field_value(title_field, title_type, &Value::U64(983u64)),
+            Err(crate::TantivyError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            Term::from_field_value(json_field, json_type, &Value::Str("hello".to_string())),
+            Err(crate::TantivyError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_term_from_field_json_path() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let json_field = schema_builder.add_json_field("json", STRING);
+        let schema = schema_builder.build();
+        let index = crate::Index::create_in_ram(schema);
+        let mut index_writer = index.writer_for_tests()?;
+        let json_val: serde_json::Map<String, serde_json::Value> = serde_json::from_str(
+            r#"{"color": "red", "attrs": {"size": -4, "weight": 2.5, "in_stock": true}}"#,
+        )?;
+        index_writer.add_document(doc!(json_field => json_val))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let inverted_index = searcher.segment_reader(0u32).inverted_index(json_field)?;
+
+        let str_term = Term::from_field_json_path(json_field, "color", &json!("red")).unwrap();
+        assert_eq!(
+            str_term.serialized_term(),
+            b"\x00\x00\x00\x00jcolor\x00sred"
+        );
+        let i64_term = Term::from_field_json_path(json_field, "attrs.size", &json!(-4)).unwrap();
+        let f64_term = Term::from_field_json_path(json_field, "attrs.weight", &json!(2.5)).unwrap();
+        let bool_term =
+            Term::from_field_json_path(json_field, "attrs.in_stock", &json!(true)).unwrap();
+        for term in [&str_term, &i64_term, &f64_term, &bool_term] {
+            assert_eq!(term.field(), json_field);
+            assert_eq!(term.typ(), Type::Json);
+            assert_eq!(inverted_index.doc_freq(term)?, 1);
+        }
+
+        let other_value = Term::from_field_json_path(json_field, "attrs.size", &json!(4)).unwrap();
+        assert_eq!(inverted_index.doc_freq(&other_value)?, 0);
+        let other_type =
+            Term::from_field_json_path(json_field, "attrs.size", &json!(-4.0)).unwrap();
+        assert_eq!(inverted_index.doc_freq(&other_type)?, 0);
+
+        assert!(Term::from_field_json_path(json_field, "color", &json!(null)).is_none());
+        assert!(Term::from_field_json_path(json_field, "color", &json!(["red"])).is_none());
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_term_i64_roundtrip_and_order() {
+        let mut schema_builder = Schema::builder();
+        let count_field = schema_builder.add_i64_field("count", INDEXED);
+        let vals = [i64::MIN, -1_000, -1, 0, 1, 1_000, i64::MAX];
+        let terms: Vec<Term> = vals
+            .iter()
+            .map(|&val| Term::from_field_i64(count_field, val))
+            .collect();
+        for (term, &val) in terms.iter().zip(vals.iter()) {
+            assert_eq!(term.value().as_i64(), Some(val));
+            assert_eq!(term.value().as_u64(), None);
+        }
+        for window in terms.windows(2) {
+            assert!(window[0] < window[1]);
+        }
+    }
+
+    #[test]
+    pub fn test_term_append_bytes() {
+        let mut schema_builder = Schema::builder();
+        let bytes_field = schema_builder.add_bytes_field("bytes", INDEXED);
+        let mut term = Term::from_field_bytes(bytes_field, b"");
+        term.append_bytes(b"segment1");
+        let segment_1 = term.clone();
+        term.append_bytes(b"/segment2");
+        let segment_1_2 = term.clone();
+        assert_eq!(term.field(), bytes_field);
+        assert_eq!(term.value().as_bytes(), Some(&b"segment1/segment2"[..]));
+        // A term sorts right after its prefixes.
+        assert!(segment_1 < segment_1_2);
+        assert!(Term::from_field_bytes(bytes_field, b"") < segment_1);
+        assert!(segment_1_2 < Term::from_field_bytes(bytes_field, b"segment2"));
+
+        term.truncate_value_bytes(b"segment1".len());
+        assert_eq!(term, segment_1);
+        term.truncate_value_bytes(0);
+        assert!(term.is_empty());
+        assert_eq!(term.field(), bytes_field);
+        assert_eq!(term.typ(), Type::Bytes);
+    }
+
+    #[test]
+    pub fn test_term_ip_addr() {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+        let mut schema_builder = Schema::builder();
+        let ip_field = schema_builder.add_ip_addr_field("ip", INDEXED);
+        let ip_v4 = IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1)).into_ipv6_addr();
+        let term = Term::from_field_ip_addr(ip_field, ip_v4);
+        assert_eq!(term.field(), ip_field);
+        assert_eq!(term.typ(), Type::IpAddr);
+        assert_eq!(term.serialized_term().len(), 4 + 1 + 16);
+        assert_eq!(term.value().as_ip_addr(), Some(ip_v4));
+        assert_eq!(
+            term.value().as_ip_addr().unwrap().to_ipv4(),
+            Some(Ipv4Addr::new(192, 168, 0, 1))
+        );
+
+        // The 192.168.0.0/16 block is a contiguous range of terms.
+        let ip_term = |ip: Ipv4Addr| Term::from_field_ip_addr(ip_field, ip.to_ipv6_mapped());
+        let block_start = ip_term(Ipv4Addr::new(192, 168, 0, 0));
+        let block_end = ip_term(Ipv4Addr::new(192, 168, 255, 255));
+        let in_block = |ip: Ipv4Addr| {
+            let term = ip_term(ip);
+            block_start <= term && term <= block_end
+        };
+        assert!(in_block(Ipv4Addr::new(192, 168, 0, 1)));
+        assert!(in_block(Ipv4Addr::new(192, 168, 12, 200)));
+        assert!(!in_block(Ipv4Addr::new(192, 167, 255, 255)));
+        assert!(!in_block(Ipv4Addr::new(192, 169, 0, 0)));
+        assert!(!in_block(Ipv4Addr::new(10, 0, 0, 1)));
+        assert!(
+            ip_term(Ipv4Addr::new(255, 255, 255, 255))
+                < Term::from_field_ip_addr(
+                    ip_field,
+                    Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)
+                )
+        );
+    }
+
+    #[test]
+    pub fn test_term_len_bytes() {
+        let mut schema_builder = Schema::builder();
+        let title_field = schema_builder.add_text_field("title", STRING);
+        let term = Term::from_field_text(title_field, "hello");
+        assert_eq!(term.len_bytes(), 5);
+        assert!(!term.is_empty());
+        let borrowed_term = Term::wrap(term.serialized_term());
+        assert_eq!(borrowed_term.len_bytes(), 5);
+        assert!(!borrowed_term.is_empty());
+        let empty_term = Term::from_field_text(title_field, "");
+        assert!(Term::wrap(empty_term.serialized_term()).is_empty());
+        assert_eq!(term.serialized_len(), 4 + 1 + 5);
+        assert_eq!(empty_term.serialized_len(), 4 + 1);
+        // malformed terms shorter than the field and the type
+        let short_term = Term::wrap(&[0u8, 0, 0][..]);
+        assert_eq!(short_term.serialized_len(), 3);
+        assert_eq!(short_term.len_bytes(), 0);
+        assert!(short_term.is_empty());
+    }
+}
+
+#[cfg(all(test, feature = "unstable"))]
+mod bench {
+    use test::Bencher;
+
+    use crate::schema::{Field, Term};
+
+    fn packed_u64_terms() -> Vec<u8> {
+        let mut packed_terms = Vec::new();
+        for val in 0u64..10_000u64 {
+            let term = Term::from_field_u64(Field::from_field_id(1), val * 7);
+            packed_terms.extend_from_slice(term.serialized_term());
+        }
+        packed_terms
+    }
+
+    #[bench]
+    fn bench_term_decode_u64_one_by_one(b: &mut Bencher) {
+        let packed_terms = packed_u64_terms();
+        b.iter(|| {
+            packed_terms
+                .chunks(13)
+                .map(|term_bytes| Term::wrap(term_bytes).value().as_u64().unwrap())
+                .sum::<u64>()
+        });
+    }
+
+    #[bench]
+    fn bench_term_decode_packed_fast_values(b: &mut Bencher) {
+        let packed_terms = packed_u64_terms();
+        b.iter(|| {
+            Term::decode_packed_fast_values(&packed_terms)
+                .unwrap()
+                .sum::<u64>()
+        });
     }
 }